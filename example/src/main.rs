@@ -5,9 +5,8 @@ use jupiter_swap_api_client::{
     JupiterSwapApiClient,
 };
 use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_sdk::{pubkey, transaction::VersionedTransaction};
+use solana_sdk::pubkey;
 use solana_sdk::{pubkey::Pubkey, signature::NullSigner};
-use tokio;
 
 const USDC_MINT: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
 const NATIVE_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
@@ -45,13 +44,9 @@ async fn main() {
 
     println!("Raw tx len: {}", swap_response.swap_transaction.len());
 
-    let versioned_transaction: VersionedTransaction =
-        bincode::deserialize(&swap_response.swap_transaction).unwrap();
-
     // Replace with a keypair or other struct implementing signer
     let null_signer = NullSigner::new(&TEST_WALLET);
-    let signed_versioned_transaction =
-        VersionedTransaction::try_new(versioned_transaction.message, &[&null_signer]).unwrap();
+    let signed_versioned_transaction = swap_response.sign(&TEST_WALLET, &[&null_signer]).unwrap();
 
     // send with rpc client...
     let rpc_client = RpcClient::new("https://api.mainnet-beta.solana.com".into());
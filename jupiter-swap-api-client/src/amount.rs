@@ -0,0 +1,43 @@
+//! Exact amount math on base unit amounts, overflows return `None` instead of losing precision
+//!
+
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+
+const BPS_DENOMINATOR: u128 = 10_000;
+
+/// `amount` reduced by `slippage_bps`, rounded down, e.g. the minimum received of an ExactIn swap
+pub fn amount_after_slippage(amount: u64, slippage_bps: u16) -> Option<u64> {
+    let factor = BPS_DENOMINATOR.checked_sub(slippage_bps as u128)?;
+    u64::try_from((amount as u128).checked_mul(factor)? / BPS_DENOMINATOR).ok()
+}
+
+/// `amount` increased by `slippage_bps`, rounded up, e.g. the maximum sent of an ExactOut swap
+pub fn amount_with_slippage(amount: u64, slippage_bps: u16) -> Option<u64> {
+    let scaled = (amount as u128).checked_mul(BPS_DENOMINATOR + slippage_bps as u128)?;
+    let rounding = (scaled % BPS_DENOMINATOR != 0) as u128;
+    u64::try_from(scaled / BPS_DENOMINATOR + rounding).ok()
+}
+
+/// `amount` in human readable units, exact since a `Decimal` holds any u64 with 28 decimals
+pub fn ui_amount(amount: u64, decimals: u8) -> Option<Decimal> {
+    let mut ui_amount = Decimal::from(amount);
+    ui_amount.set_scale(decimals as u32).ok()?;
+    Some(ui_amount)
+}
+
+/// Amount in base units of a human readable `ui_amount`, rounded down.
+/// `None` when negative or too large for a u64
+pub fn base_amount(ui_amount: Decimal, decimals: u8) -> Option<u64> {
+    let unit = Decimal::from(10u64.checked_pow(decimals as u32)?);
+    ui_amount.checked_mul(unit)?.floor().to_u64()
+}
+
+/// Output per input in human readable units, `None` when `in_amount` is 0
+pub fn effective_price(
+    in_amount: u64,
+    input_decimals: u8,
+    out_amount: u64,
+    output_decimals: u8,
+) -> Option<Decimal> {
+    ui_amount(out_amount, output_decimals)?.checked_div(ui_amount(in_amount, input_decimals)?)
+}
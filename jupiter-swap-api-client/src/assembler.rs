@@ -0,0 +1,126 @@
+//! Assemble a versioned transaction from a [`SwapInstructionsResponse`] and custom instructions
+//!
+
+use solana_sdk::{
+    address_lookup_table_account::AddressLookupTableAccount,
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0, VersionedMessage},
+    packet::PACKET_DATA_SIZE,
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+
+use crate::{swap::SwapInstructionsResponse, transaction::SwapTransactionError, Result};
+
+/// Instructions are ordered as:
+/// compute budget, before setup, setup, token ledger, before swap, swap, cleanup, after cleanup
+pub struct SwapTransactionAssembler<'a> {
+    swap_instructions: &'a SwapInstructionsResponse,
+    payer: Pubkey,
+    recent_blockhash: Hash,
+    address_lookup_table_accounts: Vec<AddressLookupTableAccount>,
+    before_setup: Vec<Instruction>,
+    before_swap: Vec<Instruction>,
+    after_cleanup: Vec<Instruction>,
+}
+
+impl<'a> SwapTransactionAssembler<'a> {
+    pub fn new(
+        swap_instructions: &'a SwapInstructionsResponse,
+        payer: Pubkey,
+        recent_blockhash: Hash,
+    ) -> Self {
+        Self {
+            swap_instructions,
+            payer,
+            recent_blockhash,
+            address_lookup_table_accounts: Vec::new(),
+            before_setup: Vec::new(),
+            before_swap: Vec::new(),
+            after_cleanup: Vec::new(),
+        }
+    }
+
+    /// Resolved accounts of `address_lookup_table_addresses`, and of any table used by the custom instructions
+    pub fn address_lookup_table_accounts(
+        mut self,
+        address_lookup_table_accounts: Vec<AddressLookupTableAccount>,
+    ) -> Self {
+        self.address_lookup_table_accounts = address_lookup_table_accounts;
+        self
+    }
+
+    pub fn before_setup(mut self, instruction: Instruction) -> Self {
+        self.before_setup.push(instruction);
+        self
+    }
+
+    /// Runs after the token ledger instruction, so a transfer increasing the input amount can be used with `use_token_ledger`
+    pub fn before_swap(mut self, instruction: Instruction) -> Self {
+        self.before_swap.push(instruction);
+        self
+    }
+
+    pub fn after_cleanup(mut self, instruction: Instruction) -> Self {
+        self.after_cleanup.push(instruction);
+        self
+    }
+
+    pub fn instructions(&self) -> Vec<Instruction> {
+        let swap_instructions = self.swap_instructions;
+        swap_instructions
+            .compute_budget_instructions
+            .iter()
+            .chain(&self.before_setup)
+            .chain(&swap_instructions.setup_instructions)
+            .chain(&swap_instructions.token_ledger_instruction)
+            .chain(&self.before_swap)
+            .chain([&swap_instructions.swap_instruction])
+            .chain(&swap_instructions.cleanup_instruction)
+            .chain(&self.after_cleanup)
+            .cloned()
+            .collect()
+    }
+
+    /// Compile an unsigned v0 transaction, failing if it does not fit in a packet
+    pub fn build(&self) -> Result<VersionedTransaction> {
+        if let Some(missing) = self
+            .swap_instructions
+            .address_lookup_table_addresses
+            .iter()
+            .find(|address| {
+                !self
+                    .address_lookup_table_accounts
+                    .iter()
+                    .any(|account| &account.key == *address)
+            })
+        {
+            return Err(SwapTransactionError::MissingAddressLookupTable(*missing).into());
+        }
+
+        let message = v0::Message::try_compile(
+            &self.payer,
+            &self.instructions(),
+            &self.address_lookup_table_accounts,
+            self.recent_blockhash,
+        )
+        .map_err(SwapTransactionError::from)?;
+        let transaction = VersionedTransaction {
+            signatures: vec![Signature::default(); message.header.num_required_signatures as usize],
+            message: VersionedMessage::V0(message),
+        };
+
+        let size = bincode::serialized_size(&transaction).map_err(SwapTransactionError::Encoding)?
+            as usize;
+        if size > PACKET_DATA_SIZE {
+            return Err(SwapTransactionError::TransactionTooLarge {
+                size,
+                max_size: PACKET_DATA_SIZE,
+            }
+            .into());
+        }
+        Ok(transaction)
+    }
+}
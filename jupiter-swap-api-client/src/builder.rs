@@ -0,0 +1,174 @@
+//! Builder for [`JupiterSwapApiClient`]
+//!
+
+use std::{sync::Arc, time::Duration};
+
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Client, Proxy,
+};
+
+use crate::{
+    cassette::Cassette,
+    dex::DexCatalog,
+    failover::{BasePaths, FailoverPolicy},
+    freshness::QuoteFreshness,
+    rate_limit::RateLimiter,
+    retry::RetryPolicy,
+    JupiterSwapApiClient, Result, DEFAULT_API_BASE_PATH,
+};
+
+/// Configures the `reqwest::Client` shared by every request of a [`JupiterSwapApiClient`]
+#[derive(Debug)]
+pub struct JupiterSwapApiClientBuilder {
+    base_paths: Vec<String>,
+    failover_policy: FailoverPolicy,
+    api_base_path: String,
+    client: Option<Client>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    user_agent: Option<HeaderValue>,
+    default_headers: HeaderMap,
+    proxy: Option<Proxy>,
+    retry_policy: Option<RetryPolicy>,
+    rate_limiter: Option<RateLimiter>,
+    cassette: Option<Cassette>,
+    dex_catalog: Option<DexCatalog>,
+    quote_freshness: Option<QuoteFreshness>,
+}
+
+impl JupiterSwapApiClientBuilder {
+    pub fn new(base_path: String) -> Self {
+        Self {
+            base_paths: vec![base_path],
+            failover_policy: FailoverPolicy::default(),
+            api_base_path: DEFAULT_API_BASE_PATH.to_string(),
+            client: None,
+            connect_timeout: None,
+            timeout: None,
+            user_agent: None,
+            default_headers: HeaderMap::new(),
+            proxy: None,
+            retry_policy: None,
+            rate_limiter: None,
+            cassette: None,
+            dex_catalog: None,
+            quote_freshness: None,
+        }
+    }
+
+    /// Base path tried after the previous ones failed, e.g. the public API behind a self-hosted one
+    pub fn fallback_base_path(mut self, base_path: String) -> Self {
+        self.base_paths.push(base_path);
+        self
+    }
+
+    /// How fallback base paths are used, failover by default
+    pub fn failover_policy(mut self, failover_policy: FailoverPolicy) -> Self {
+        self.failover_policy = failover_policy;
+        self
+    }
+
+    /// Base path of the Jupiter APIs other than the swap API, `https://api.jup.ag` by default
+    pub fn api_base_path(mut self, api_base_path: String) -> Self {
+        self.api_base_path = api_base_path;
+        self
+    }
+
+    /// Use a pre-built client, the connection options of this builder are then ignored
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Timeout for establishing a connection
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Total timeout of a request, from sending it to reading the whole body
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: HeaderValue) -> Self {
+        self.user_agent = Some(user_agent);
+        self
+    }
+
+    /// Header sent with every request, e.g. `x-api-key` for the paid hosted APIs
+    pub fn default_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.default_headers.insert(name, value);
+        self
+    }
+
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Retry transient failures, by default only `quote` is retried
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Throttle requests client side, every attempt of a retried request takes a permit
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Record the interactions to a file, or replay them from it without sending any request
+    pub fn cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Reject quote requests with `dexes` or `excluded_dexes` labels missing from the catalog
+    pub fn dex_catalog(mut self, dex_catalog: DexCatalog) -> Self {
+        self.dex_catalog = Some(dex_catalog);
+        self
+    }
+
+    /// Reject stale quotes in `swap` and `swap_instructions` with `JupiterError::StaleQuote`
+    pub fn quote_freshness(mut self, quote_freshness: QuoteFreshness) -> Self {
+        self.quote_freshness = Some(quote_freshness);
+        self
+    }
+
+    pub fn build(self) -> Result<JupiterSwapApiClient> {
+        let client = match self.client {
+            Some(client) => client,
+            None => {
+                let mut builder = Client::builder().default_headers(self.default_headers);
+                if let Some(connect_timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(connect_timeout);
+                }
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(user_agent) = self.user_agent {
+                    builder = builder.user_agent(user_agent);
+                }
+                if let Some(proxy) = self.proxy {
+                    builder = builder.proxy(proxy);
+                }
+                builder.build()?
+            }
+        };
+        Ok(JupiterSwapApiClient {
+            base_paths: Arc::new(BasePaths::new(self.base_paths, self.failover_policy)),
+            api_base_path: self.api_base_path,
+            client,
+            retry_policy: self.retry_policy,
+            rate_limiter: self.rate_limiter,
+            cassette: self.cassette.map(Arc::new),
+            dex_catalog: self.dex_catalog.map(Arc::new),
+            quote_freshness: self.quote_freshness,
+            latest_context_slot: Arc::default(),
+        })
+    }
+}
@@ -0,0 +1,169 @@
+//! Record API interactions to a file and replay them without network access
+//!
+
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::PathBuf,
+    sync::Mutex,
+    time::Duration,
+};
+
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    http::{ApiRequest, ApiResponse},
+    Result,
+};
+
+#[derive(Error, Debug)]
+pub enum CassetteError {
+    /// No recorded interaction of the replayed cassette matches the request
+    #[error("no recorded interaction for {method} {path}{}", query.as_ref().map(|query| format!("?{query}")).unwrap_or_default())]
+    Miss {
+        method: String,
+        path: String,
+        query: Option<String>,
+    },
+    /// The cassette file could not be read or written
+    #[error("cassette file error: {0}")]
+    Io(#[from] io::Error),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Send requests and keep every interaction, written to the file by [`Cassette::save`]
+    Record,
+    /// Serve the recorded interactions, never touching the network
+    Replay,
+}
+
+/// A request and its response, as stored in the cassette file
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Interaction {
+    pub method: String,
+    /// Path of the endpoint and its path parameters, independent of the base path
+    pub path: String,
+    /// Query string with its parameters sorted
+    pub query: Option<String>,
+    pub body: Option<serde_json::Value>,
+    pub status: u16,
+    pub retry_after: Option<u64>,
+    pub response_body: String,
+}
+
+impl Interaction {
+    fn matches(&self, request: &ApiRequest) -> bool {
+        self.method == request.method.as_str()
+            && self.path == request.path
+            && self.query == request.query.as_deref().map(normalize_query)
+            && self.body == request.body
+    }
+}
+
+fn normalize_query(query: &str) -> String {
+    let mut parameters = query.split('&').collect::<Vec<_>>();
+    parameters.sort_unstable();
+    parameters.join("&")
+}
+
+#[derive(Debug)]
+pub struct Cassette {
+    mode: CassetteMode,
+    path: PathBuf,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    interactions: Vec<Interaction>,
+    /// Interactions already served in replay mode
+    replayed: Vec<bool>,
+}
+
+impl Cassette {
+    /// Record to `path`, overwriting it on the first interaction
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self {
+            mode: CassetteMode::Record,
+            path: path.into(),
+            state: Mutex::default(),
+        }
+    }
+
+    /// Replay the interactions recorded in `path`.
+    /// Matching requests are served in recorded order, each interaction at most once
+    pub fn replay(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let interactions: Vec<Interaction> =
+            serde_json::from_slice(&fs::read(&path).map_err(CassetteError::from)?)
+                .map_err(|e| CassetteError::from(io::Error::new(ErrorKind::InvalidData, e)))?;
+        Ok(Self {
+            mode: CassetteMode::Replay,
+            path,
+            state: Mutex::new(State {
+                replayed: vec![false; interactions.len()],
+                interactions,
+            }),
+        })
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.state.lock().unwrap().interactions.clone()
+    }
+
+    pub(crate) fn replay_request(&self, request: &ApiRequest) -> Result<ApiResponse> {
+        let mut state = self.state.lock().unwrap();
+        let State {
+            interactions,
+            replayed,
+        } = &mut *state;
+        let (interaction, replayed) = interactions
+            .iter()
+            .zip(replayed.iter_mut())
+            .find(|(interaction, replayed)| !**replayed && interaction.matches(request))
+            .ok_or_else(|| CassetteError::Miss {
+                method: request.method.to_string(),
+                path: request.path.clone(),
+                query: request.query.clone(),
+            })?;
+        *replayed = true;
+        Ok(ApiResponse {
+            status: StatusCode::from_u16(interaction.status)
+                .map_err(|e| CassetteError::from(io::Error::new(ErrorKind::InvalidData, e)))?,
+            retry_after: interaction.retry_after.map(Duration::from_secs),
+            body: interaction.response_body.clone(),
+        })
+    }
+
+    pub(crate) fn record_interaction(&self, request: &ApiRequest, response: &ApiResponse) {
+        self.state.lock().unwrap().interactions.push(Interaction {
+            method: request.method.to_string(),
+            path: request.path.clone(),
+            query: request.query.as_deref().map(normalize_query),
+            body: request.body.clone(),
+            status: response.status.as_u16(),
+            retry_after: response
+                .retry_after
+                .map(|retry_after| retry_after.as_secs()),
+            response_body: response.body.clone(),
+        });
+    }
+
+    /// Write the interactions recorded so far to the file, replacing its content
+    pub async fn save(&self) -> Result<()> {
+        let contents = serde_json::to_vec_pretty(&self.interactions())
+            .map_err(|e| CassetteError::from(io::Error::new(ErrorKind::InvalidData, e)))?;
+        tokio::fs::write(&self.path, contents)
+            .await
+            .map_err(CassetteError::from)?;
+        Ok(())
+    }
+}
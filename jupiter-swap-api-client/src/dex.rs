@@ -0,0 +1,225 @@
+//! DEX catalog built from `/program-id-to-label` and mint reachability from `/indexed-route-map`
+//!
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use thiserror::Error;
+
+use crate::{
+    quote::QuoteRequest,
+    route_plan_with_metadata::SwapInfo,
+    rpc::SolanaRpc,
+    serde_helpers::{field_as_string, map_keys_as_string},
+    Result,
+};
+
+/// A DEX label is not known to the API
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("unknown dex label {0}")]
+pub struct UnknownDex(pub String);
+
+/// Response of `/program-id-to-label`
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(transparent)]
+pub struct ProgramIdToLabel(#[serde(with = "map_keys_as_string")] pub HashMap<Pubkey, String>);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Dex {
+    pub program_id: Pubkey,
+    /// Label used by `QuoteRequest::dexes` and `SwapInfo::label`
+    pub label: String,
+}
+
+/// DEXes known to the API, to validate labels before quoting and to resolve labels to programs
+#[derive(Clone, Debug, Default)]
+pub struct DexCatalog {
+    dexes: Vec<Dex>,
+    by_program_id: HashMap<Pubkey, usize>,
+    by_label: HashMap<String, usize>,
+    /// Program owning each AMM account loaded with `load_amm_owners`
+    amm_programs: HashMap<Pubkey, Pubkey>,
+}
+
+impl DexCatalog {
+    pub fn new(dexes: Vec<Dex>) -> Self {
+        let by_program_id = dexes
+            .iter()
+            .enumerate()
+            .map(|(index, dex)| (dex.program_id, index))
+            .collect();
+        let by_label = dexes
+            .iter()
+            .enumerate()
+            .map(|(index, dex)| (dex.label.clone(), index))
+            .collect();
+        Self {
+            dexes,
+            by_program_id,
+            by_label,
+            amm_programs: HashMap::new(),
+        }
+    }
+
+    pub fn dexes(&self) -> &[Dex] {
+        &self.dexes
+    }
+
+    pub fn by_program_id(&self, program_id: &Pubkey) -> Option<&Dex> {
+        self.by_program_id
+            .get(program_id)
+            .map(|index| &self.dexes[*index])
+    }
+
+    pub fn by_label(&self, label: &str) -> Option<&Dex> {
+        self.by_label.get(label).map(|index| &self.dexes[*index])
+    }
+
+    /// Record that `amm_key` is owned by `program_id`, see [`DexCatalog::for_swap`]
+    pub fn add_amm(&mut self, amm_key: Pubkey, program_id: Pubkey) {
+        self.amm_programs.insert(amm_key, program_id);
+    }
+
+    /// Fetch the owner of the AMM accounts of `swap_infos` not loaded yet, and record those owned by a known DEX
+    pub async fn load_amm_owners<'a, R: SolanaRpc + ?Sized>(
+        &mut self,
+        rpc: &R,
+        swap_infos: impl IntoIterator<Item = &'a SwapInfo>,
+    ) -> Result<()> {
+        let amm_keys = swap_infos
+            .into_iter()
+            .map(|swap_info| swap_info.amm_key)
+            .filter(|amm_key| !self.amm_programs.contains_key(amm_key))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        if amm_keys.is_empty() {
+            return Ok(());
+        }
+        let accounts = rpc.get_accounts(&amm_keys).await?;
+        for (amm_key, account) in amm_keys.into_iter().zip(accounts) {
+            if let Some(account) =
+                account.filter(|account| self.by_program_id.contains_key(&account.owner))
+            {
+                self.add_amm(amm_key, account.owner);
+            }
+        }
+        Ok(())
+    }
+
+    /// DEX a step of a route plan swaps through.
+    /// Resolved from the owner of `amm_key` when it was loaded, as several programs can share a label,
+    /// and from the label otherwise
+    pub fn for_swap(&self, swap_info: &SwapInfo) -> Option<&Dex> {
+        self.amm_programs
+            .get(&swap_info.amm_key)
+            .and_then(|program_id| self.by_program_id(program_id))
+            .or_else(|| self.by_label(&swap_info.label))
+    }
+
+    /// Fail on the first label not known to the API
+    pub fn validate_labels<'a>(&self, labels: impl IntoIterator<Item = &'a String>) -> Result<()> {
+        match labels
+            .into_iter()
+            .find(|label| self.by_label(label).is_none())
+        {
+            Some(label) => Err(UnknownDex(label.clone()).into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Check `dexes` and `excluded_dexes`, a misspelled label would otherwise be ignored by the API
+    pub fn validate_quote_request(&self, quote_request: &QuoteRequest) -> Result<()> {
+        self.validate_labels(
+            quote_request
+                .dexes
+                .iter()
+                .chain(&quote_request.excluded_dexes)
+                .flatten(),
+        )
+    }
+}
+
+impl From<ProgramIdToLabel> for DexCatalog {
+    fn from(ProgramIdToLabel(program_id_to_label): ProgramIdToLabel) -> Self {
+        let mut dexes = program_id_to_label
+            .into_iter()
+            .map(|(program_id, label)| Dex { program_id, label })
+            .collect::<Vec<_>>();
+        dexes.sort_by(|a, b| a.label.cmp(&b.label));
+        Self::new(dexes)
+    }
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedRouteMapRequest {
+    /// Only include mints that can be swapped in a single hop
+    pub only_direct_routes: Option<bool>,
+}
+
+/// Response of `/indexed-route-map`, can be saved as JSON and loaded for offline queries
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedRouteMap {
+    pub mint_keys: Vec<MintKey>,
+    /// Index of an input mint in `mint_keys` to the indexes of the output mints it can be swapped to
+    pub indexed_route_map: HashMap<usize, Vec<usize>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct MintKey(#[serde(with = "field_as_string")] pub Pubkey);
+
+/// Which mints can be swapped to which, built from an [`IndexedRouteMap`]
+#[derive(Clone, Debug, Default)]
+pub struct RouteMap {
+    routes: HashMap<Pubkey, HashSet<Pubkey>>,
+}
+
+impl From<&IndexedRouteMap> for RouteMap {
+    fn from(indexed_route_map: &IndexedRouteMap) -> Self {
+        let mint = |index: &usize| indexed_route_map.mint_keys.get(*index).map(|key| key.0);
+        let routes = indexed_route_map
+            .indexed_route_map
+            .iter()
+            .filter_map(|(input, outputs)| {
+                Some((mint(input)?, outputs.iter().filter_map(mint).collect()))
+            })
+            .collect();
+        Self { routes }
+    }
+}
+
+impl RouteMap {
+    pub fn mints(&self) -> impl Iterator<Item = &Pubkey> {
+        self.routes.keys()
+    }
+
+    /// Mints `input_mint` can be swapped to in one quote
+    pub fn outputs(&self, input_mint: &Pubkey) -> impl Iterator<Item = &Pubkey> {
+        self.routes.get(input_mint).into_iter().flatten()
+    }
+
+    pub fn has_route(&self, input_mint: &Pubkey, output_mint: &Pubkey) -> bool {
+        matches!(self.routes.get(input_mint), Some(outputs) if outputs.contains(output_mint))
+    }
+
+    /// Mints reachable from `input_mint` by chaining at most `max_quotes` quotes
+    pub fn reachable(&self, input_mint: &Pubkey, max_quotes: usize) -> HashSet<Pubkey> {
+        let mut reachable = HashSet::new();
+        let mut queue = VecDeque::from([(*input_mint, 0)]);
+        while let Some((mint, quotes)) = queue.pop_front() {
+            if quotes == max_quotes {
+                continue;
+            }
+            for output in self.outputs(&mint) {
+                if output != input_mint && reachable.insert(*output) {
+                    queue.push_back((*output, quotes + 1));
+                }
+            }
+        }
+        reachable
+    }
+}
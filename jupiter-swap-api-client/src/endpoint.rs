@@ -0,0 +1,55 @@
+//! Endpoints of the Jupiter API called by the client
+//!
+
+use std::fmt;
+
+/// Declares `Endpoint` with its paths, so that `Endpoint::ALL` lists every variant
+macro_rules! endpoints {
+    ($($endpoint:ident => $path:literal,)*) => {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum Endpoint {
+            $($endpoint,)*
+        }
+
+        impl Endpoint {
+            /// Every endpoint, in declaration order
+            pub const ALL: &'static [Endpoint] = &[$(Self::$endpoint,)*];
+
+            pub fn path(&self) -> &'static str {
+                match self {
+                    $(Self::$endpoint => $path,)*
+                }
+            }
+        }
+    };
+}
+
+endpoints! {
+    Quote => "/quote",
+    Swap => "/swap",
+    SwapInstructions => "/swap-instructions",
+    ProgramIdToLabel => "/program-id-to-label",
+    IndexedRouteMap => "/indexed-route-map",
+    Price => "/price/v2",
+    Token => "/tokens/v1/token",
+    TaggedTokens => "/tokens/v1/tagged",
+    TradableTokens => "/tokens/v1/mints/tradable",
+    CreateLimitOrder => "/limit/v2/createOrder",
+    CancelLimitOrders => "/limit/v2/cancelOrders",
+    OpenLimitOrders => "/limit/v2/openOrders",
+    LimitOrderHistory => "/limit/v2/orderHistory",
+    CreateRecurringOrder => "/recurring/v1/createOrder",
+    CancelRecurringOrder => "/recurring/v1/cancelOrder",
+    DepositRecurringOrder => "/recurring/v1/priceDeposit",
+    WithdrawRecurringOrder => "/recurring/v1/priceWithdraw",
+    RecurringOrders => "/recurring/v1/getRecurringOrders",
+    RecurringExecute => "/recurring/v1/execute",
+    UltraOrder => "/ultra/v1/order",
+    UltraExecute => "/ultra/v1/execute",
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.path())
+    }
+}
@@ -0,0 +1,199 @@
+//! Error type returned by [`crate::JupiterSwapApiClient`]
+//!
+
+use std::{fmt, time::Duration};
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use crate::{
+    cassette::CassetteError, dex::UnknownDex, endpoint::Endpoint, executor::ExecutionError,
+    freshness::StaleQuote, jupiter_instruction::InvalidJupiterInstruction, route_graph::RouteError,
+    rpc::RpcError, transaction::SwapTransactionError, ultra::UltraError,
+    verifier::VerificationError,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum JupiterError {
+    /// The request could not be sent or the response could not be read: connection refused, timeout, TLS...
+    #[error("transport error: {0}")]
+    Transport(#[from] reqwest::Error),
+    /// The API answered with a non-2xx status
+    #[error("request status not ok: {status}, error code: {error_code:?}, body: {body}")]
+    Api {
+        status: StatusCode,
+        /// Jupiter error code parsed from the body, when present
+        error_code: Option<ErrorCode>,
+        /// Raw response body
+        body: String,
+        /// Delay requested by the `Retry-After` header, when given in seconds
+        retry_after: Option<Duration>,
+    },
+    /// The response body did not match the expected schema
+    #[error("failed to deserialize response: {source}, body: {body}")]
+    Deserialize {
+        #[source]
+        source: serde_json::Error,
+        /// Raw response body
+        body: String,
+    },
+    /// The request could not be encoded as a query string
+    #[error("failed to encode query string: {0}")]
+    QueryEncoding(#[from] serde_qs::Error),
+    /// The request body could not be encoded as JSON
+    #[error("failed to encode request body: {0}")]
+    BodyEncoding(#[source] serde_json::Error),
+    /// A string that is not a valid `SwapMode`
+    #[error("{0} is not a valid SwapMode")]
+    InvalidSwapMode(String),
+    /// A `RateLimit` with a rate that is not positive or a burst of zero
+    #[error("invalid rate limit: {0}")]
+    InvalidRateLimit(&'static str),
+    /// No permit was available from the client side rate limiter in fail fast mode
+    #[error("client side rate limit reached for {0}")]
+    RateLimited(Endpoint),
+    /// A transaction returned by the API could not be decoded, checked, signed or assembled
+    #[error(transparent)]
+    Transaction(#[from] SwapTransactionError),
+    /// Error returned by a [`crate::rpc::SolanaRpc`] implementation
+    #[error(transparent)]
+    Rpc(#[from] RpcError),
+    /// A swap could not be simulated, sent or confirmed by [`crate::executor::SwapExecutor`]
+    #[error(transparent)]
+    Execution(#[from] ExecutionError),
+    #[error(transparent)]
+    InvalidJupiterInstruction(#[from] InvalidJupiterInstruction),
+    /// The transaction built by the API does not match the swap request
+    #[error("swap transaction verification failed: {0}")]
+    Verification(#[from] VerificationError),
+    /// The quote is older than the `QuoteFreshness` limits
+    #[error(transparent)]
+    StaleQuote(#[from] StaleQuote),
+    /// The route plan is not a valid split or leaves the mint allowlist
+    #[error(transparent)]
+    Route(#[from] RouteError),
+    #[error(transparent)]
+    Ultra(#[from] UltraError),
+    #[error(transparent)]
+    UnknownDex(#[from] UnknownDex),
+    #[error(transparent)]
+    Cassette(#[from] CassetteError),
+}
+
+impl JupiterError {
+    pub(crate) fn api(status: StatusCode, retry_after: Option<Duration>, body: String) -> Self {
+        let error_code = serde_json::from_str::<ApiErrorBody>(&body)
+            .ok()
+            .and_then(|body| body.error_code)
+            .map(|code| ErrorCode::from(code.as_str()));
+        Self::Api {
+            status,
+            error_code,
+            body,
+            retry_after,
+        }
+    }
+
+    /// HTTP status of a non-2xx response
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Api { status, .. } => Some(*status),
+            Self::Transport(e) => e.status(),
+            _ => None,
+        }
+    }
+
+    pub fn error_code(&self) -> Option<&ErrorCode> {
+        match self {
+            Self::Api { error_code, .. } => error_code.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::Api { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// Connection errors, timeouts, 429 and 5xx responses, which are worth retrying or sending elsewhere
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Transport(e) => e.is_connect() || e.is_timeout(),
+            _ => self.is_rate_limited() || self.is_server_error(),
+        }
+    }
+
+    /// No route exists between the requested mints for this amount
+    pub fn is_no_route(&self) -> bool {
+        matches!(
+            self.error_code(),
+            Some(ErrorCode::CouldNotFindAnyRoute | ErrorCode::NoRoutesFound)
+        )
+    }
+
+    /// The API answered 429 Too Many Requests
+    pub fn is_rate_limited(&self) -> bool {
+        self.status() == Some(StatusCode::TOO_MANY_REQUESTS)
+    }
+
+    /// The API answered with a 5xx status
+    pub fn is_server_error(&self) -> bool {
+        matches!(self.status(), Some(status) if status.is_server_error())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiErrorBody {
+    error_code: Option<String>,
+}
+
+/// Error codes returned by the Jupiter API in the `errorCode` field
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorCode {
+    CouldNotFindAnyRoute,
+    NoRoutesFound,
+    TokenNotTradable,
+    CircularArbitrageIsDisabled,
+    RoutePlanDoesNotConsumeAllTheAmount,
+    /// Error code not known to this client
+    Other(String),
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::CouldNotFindAnyRoute => "COULD_NOT_FIND_ANY_ROUTE",
+            Self::NoRoutesFound => "NO_ROUTES_FOUND",
+            Self::TokenNotTradable => "TOKEN_NOT_TRADABLE",
+            Self::CircularArbitrageIsDisabled => "CIRCULAR_ARBITRAGE_IS_DISABLED",
+            Self::RoutePlanDoesNotConsumeAllTheAmount => {
+                "ROUTE_PLAN_DOES_NOT_CONSUME_ALL_THE_AMOUNT"
+            }
+            Self::Other(code) => code,
+        }
+    }
+}
+
+impl From<&str> for ErrorCode {
+    fn from(code: &str) -> Self {
+        match code {
+            "COULD_NOT_FIND_ANY_ROUTE" => Self::CouldNotFindAnyRoute,
+            "NO_ROUTES_FOUND" => Self::NoRoutesFound,
+            "TOKEN_NOT_TRADABLE" => Self::TokenNotTradable,
+            "CIRCULAR_ARBITRAGE_IS_DISABLED" => Self::CircularArbitrageIsDisabled,
+            "ROUTE_PLAN_DOES_NOT_CONSUME_ALL_THE_AMOUNT" => {
+                Self::RoutePlanDoesNotConsumeAllTheAmount
+            }
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
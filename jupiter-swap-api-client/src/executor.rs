@@ -0,0 +1,297 @@
+//! Quote, build, sign, send and confirm a swap in one call
+//!
+
+use std::time::Duration;
+
+use solana_sdk::{
+    message::VersionedMessage,
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::signers::Signers,
+    transaction::{TransactionError, VersionedTransaction},
+};
+use thiserror::Error;
+use tokio::time::Instant;
+
+use crate::{
+    assembler::SwapTransactionAssembler,
+    error::JupiterError,
+    preflight::{set_compute_unit_limit, simulate},
+    quote::{QuoteRequest, QuoteResponse},
+    rpc::SolanaRpc,
+    swap::SwapRequest,
+    swap_result::SwapResult,
+    transaction::{check_fee_payer, sign_transaction},
+    transaction_config::TransactionConfig,
+    verifier::SwapVerifier,
+    JupiterSwapApiClient, Result,
+};
+
+#[derive(Error, Debug)]
+pub enum ExecutionError {
+    #[error("simulation failed: {err}, logs: {logs:?}")]
+    SimulationFailed {
+        err: TransactionError,
+        logs: Vec<String>,
+    },
+    #[error("transaction has no SetComputeUnitLimit instruction")]
+    MissingComputeUnitLimit,
+    #[error("transaction {signature} failed: {err}")]
+    TransactionFailed {
+        signature: Signature,
+        err: TransactionError,
+    },
+    /// The balance of a mint of the swap moved the wrong way, or by more than a `u64`
+    #[error("unexpected balance change of {change} for mint {mint}")]
+    UnexpectedBalanceChange { mint: Pubkey, change: i128 },
+    /// The blockhash of the last sent transaction expired before it was confirmed
+    #[error("blockhash expired before transaction {0} was confirmed")]
+    BlockhashExpired(Signature),
+    /// The transaction was not confirmed within `confirmation_timeout`, it may still land
+    #[error("transaction {0} was not confirmed in time")]
+    ConfirmationTimeout(Signature),
+}
+
+#[derive(Debug)]
+pub struct SwapExecutorConfig {
+    pub transaction_config: TransactionConfig,
+    /// Build with `/swap-instructions` and assemble the transaction client side instead of using `/swap`
+    pub use_swap_instructions: bool,
+    /// Check the built transaction with [`SwapVerifier`] before signing it
+    ///
+    /// Default: true
+    pub verify: bool,
+    /// SOL transfers accepted by the verifier, e.g. the Jito tip accounts with `JitoTipLamports`
+    pub allowed_transfer_destinations: Vec<Pubkey>,
+    /// Simulate before sending and fail without sending if the simulation fails
+    pub simulate: bool,
+    /// Set the compute unit limit to the simulated units plus this margin, requires `simulate`
+    pub compute_unit_limit_margin_bps: Option<u16>,
+    /// Quote and build again when the blockhash expires before the transaction is confirmed,
+    /// or when the quote is rejected by the client's `QuoteFreshness`
+    pub max_rebuilds: u32,
+    pub confirmation_poll_interval: Duration,
+    /// Give up waiting for a sent transaction, also when it was processed but never confirmed
+    pub confirmation_timeout: Duration,
+}
+
+impl Default for SwapExecutorConfig {
+    fn default() -> Self {
+        Self {
+            transaction_config: TransactionConfig::default(),
+            use_swap_instructions: false,
+            verify: true,
+            allowed_transfer_destinations: Vec::new(),
+            simulate: false,
+            compute_unit_limit_margin_bps: None,
+            max_rebuilds: 2,
+            confirmation_poll_interval: Duration::from_millis(500),
+            confirmation_timeout: Duration::from_secs(90),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SwapExecution {
+    pub signature: Signature,
+    pub slot: u64,
+    /// Quote of the transaction that landed
+    pub quote_response: QuoteResponse,
+    /// Input amount that actually left the user's accounts
+    pub in_amount: u64,
+    /// Output amount that actually reached the user's accounts
+    pub out_amount: u64,
+}
+
+pub struct SwapExecutor<R> {
+    client: JupiterSwapApiClient,
+    rpc: R,
+    config: SwapExecutorConfig,
+}
+
+struct BuiltTransaction {
+    transaction: VersionedTransaction,
+    last_valid_block_height: u64,
+}
+
+impl<R: SolanaRpc> SwapExecutor<R> {
+    pub fn new(client: JupiterSwapApiClient, rpc: R, config: SwapExecutorConfig) -> Self {
+        Self {
+            client,
+            rpc,
+            config,
+        }
+    }
+
+    pub fn rpc(&self) -> &R {
+        &self.rpc
+    }
+
+    /// Run the swap, signed by `signers` for `user_public_key`
+    pub async fn execute<T: Signers + ?Sized>(
+        &self,
+        quote_request: &QuoteRequest,
+        user_public_key: Pubkey,
+        signers: &T,
+    ) -> Result<SwapExecution> {
+        let mut rebuilds = 0;
+        loop {
+            let quote_response = self.client.quote(quote_request).await?;
+            let BuiltTransaction {
+                mut transaction,
+                last_valid_block_height,
+            } = match self.build(&quote_response, user_public_key).await {
+                Err(JupiterError::StaleQuote(_)) if rebuilds < self.config.max_rebuilds => {
+                    rebuilds += 1;
+                    continue;
+                }
+                built_transaction => built_transaction?,
+            };
+            check_fee_payer(&transaction, &user_public_key)?;
+            if self.config.simulate {
+                let report = simulate(&self.rpc, &transaction, &user_public_key)
+                    .await?
+                    .into_result()?;
+                if let Some(units) = self
+                    .config
+                    .compute_unit_limit_margin_bps
+                    .and_then(|margin_bps| report.compute_unit_limit(margin_bps))
+                {
+                    set_compute_unit_limit(&mut transaction, units)?;
+                }
+            }
+            sign_transaction(&mut transaction, signers)?;
+
+            let signature = self.rpc.send_transaction(&transaction).await?;
+            match self.confirm(&signature, last_valid_block_height).await? {
+                Some(slot) => {
+                    return self
+                        .execution(signature, slot, quote_response, &user_public_key)
+                        .await
+                }
+                None if rebuilds < self.config.max_rebuilds => rebuilds += 1,
+                None => return Err(ExecutionError::BlockhashExpired(signature).into()),
+            }
+        }
+    }
+
+    async fn build(
+        &self,
+        quote_response: &QuoteResponse,
+        user_public_key: Pubkey,
+    ) -> Result<BuiltTransaction> {
+        let swap_request = SwapRequest {
+            user_public_key,
+            quote_response: quote_response.clone(),
+            config: self.config.transaction_config.clone(),
+        };
+        if !self.config.use_swap_instructions {
+            let swap_response = self.client.swap(&swap_request).await?;
+            let transaction = swap_response.versioned_transaction()?;
+            if self.config.verify {
+                let table_addresses = match &transaction.message {
+                    VersionedMessage::Legacy(_) => Vec::new(),
+                    VersionedMessage::V0(message) => message
+                        .address_table_lookups
+                        .iter()
+                        .map(|table_lookup| table_lookup.account_key)
+                        .collect(),
+                };
+                let address_lookup_table_accounts =
+                    self.rpc.get_address_lookup_tables(&table_addresses).await?;
+                self.verifier(&swap_request)
+                    .address_lookup_table_accounts(address_lookup_table_accounts)
+                    .verify_transaction(&transaction)?;
+            }
+            return Ok(BuiltTransaction {
+                transaction,
+                last_valid_block_height: swap_response.last_valid_block_height,
+            });
+        }
+
+        let swap_instructions = self.client.swap_instructions(&swap_request).await?;
+        if self.config.verify {
+            self.verifier(&swap_request)
+                .verify_swap_instructions(&swap_instructions)?;
+        }
+        let address_lookup_table_accounts = self
+            .rpc
+            .get_address_lookup_tables(&swap_instructions.address_lookup_table_addresses)
+            .await?;
+        let (recent_blockhash, last_valid_block_height) = self.rpc.get_latest_blockhash().await?;
+        let transaction =
+            SwapTransactionAssembler::new(&swap_instructions, user_public_key, recent_blockhash)
+                .address_lookup_table_accounts(address_lookup_table_accounts)
+                .build()?;
+        Ok(BuiltTransaction {
+            transaction,
+            last_valid_block_height,
+        })
+    }
+
+    fn verifier<'a>(&self, swap_request: &'a SwapRequest) -> SwapVerifier<'a> {
+        self.config
+            .allowed_transfer_destinations
+            .iter()
+            .fold(SwapVerifier::new(swap_request), |verifier, destination| {
+                verifier.allow_transfer_to(*destination)
+            })
+    }
+
+    /// Slot the transaction was confirmed in, `None` once the blockhash expired.
+    /// A transaction known to the cluster may still confirm, so only an unknown one can expire
+    async fn confirm(
+        &self,
+        signature: &Signature,
+        last_valid_block_height: u64,
+    ) -> Result<Option<u64>> {
+        let deadline = Instant::now() + self.config.confirmation_timeout;
+        loop {
+            match self.rpc.get_signature_status(signature).await? {
+                Some(status) if status.confirmed => {
+                    return match status.err {
+                        Some(err) => Err(ExecutionError::TransactionFailed {
+                            signature: *signature,
+                            err,
+                        }
+                        .into()),
+                        None => Ok(Some(status.slot)),
+                    };
+                }
+                Some(_) => {}
+                None => {
+                    if self.rpc.get_block_height().await? > last_valid_block_height {
+                        return Ok(None);
+                    }
+                }
+            }
+            if Instant::now() >= deadline {
+                return Err(ExecutionError::ConfirmationTimeout(*signature).into());
+            }
+            tokio::time::sleep(self.config.confirmation_poll_interval).await;
+        }
+    }
+
+    async fn execution(
+        &self,
+        signature: Signature,
+        slot: u64,
+        quote_response: QuoteResponse,
+        user_public_key: &Pubkey,
+    ) -> Result<SwapExecution> {
+        let confirmed_transaction = self.rpc.get_transaction(&signature).await?;
+        let swap_result = SwapResult::parse(
+            &confirmed_transaction,
+            user_public_key,
+            &quote_response.input_mint,
+            &quote_response.output_mint,
+        )?;
+        Ok(SwapExecution {
+            signature,
+            slot,
+            quote_response,
+            in_amount: swap_result.in_amount,
+            out_amount: swap_result.out_amount,
+        })
+    }
+}
@@ -0,0 +1,156 @@
+//! Failover and hedged requests across several base paths
+//!
+
+use std::{
+    future::Future,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::Result;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FailoverPolicy {
+    /// How long a base path is skipped after a transient failure
+    pub cooldown: Duration,
+    pub mode: FailoverMode,
+}
+
+impl Default for FailoverPolicy {
+    fn default() -> Self {
+        Self {
+            cooldown: Duration::from_secs(30),
+            mode: FailoverMode::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum FailoverMode {
+    /// Use the first healthy base path, move to the next one on transient errors
+    #[default]
+    Failover,
+    /// Send the `quote` to a second base path when the first one has not answered within `latency_budget`,
+    /// the first successful response wins. Other requests fail over
+    Hedged { latency_budget: Duration },
+}
+
+/// Ordered base paths with their health
+#[derive(Debug)]
+pub(crate) struct BasePaths {
+    base_paths: Vec<String>,
+    /// Time until which each base path is considered unhealthy
+    unhealthy_until: Vec<Mutex<Option<Instant>>>,
+    policy: FailoverPolicy,
+}
+
+impl BasePaths {
+    pub(crate) fn new(base_paths: Vec<String>, policy: FailoverPolicy) -> Self {
+        Self {
+            unhealthy_until: base_paths.iter().map(|_| Mutex::new(None)).collect(),
+            base_paths,
+            policy,
+        }
+    }
+
+    pub(crate) fn primary(&self) -> &str {
+        &self.base_paths[0]
+    }
+
+    pub(crate) fn all(&self) -> &[String] {
+        &self.base_paths
+    }
+
+    fn is_healthy(&self, index: usize, now: Instant) -> bool {
+        match *self.unhealthy_until[index].lock().unwrap() {
+            Some(until) => until <= now,
+            None => true,
+        }
+    }
+
+    /// Healthy base paths first, in the configured order, then the ones cooling down
+    fn candidates(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let (mut healthy, unhealthy): (Vec<usize>, Vec<usize>) =
+            (0..self.base_paths.len()).partition(|&index| self.is_healthy(index, now));
+        healthy.extend(unhealthy);
+        healthy
+    }
+
+    fn record<T>(&self, index: usize, result: Result<T>) -> Result<T> {
+        let mut unhealthy_until = self.unhealthy_until[index].lock().unwrap();
+        match &result {
+            Ok(_) => *unhealthy_until = None,
+            Err(error) if error.is_transient() => {
+                *unhealthy_until = Some(Instant::now() + self.policy.cooldown)
+            }
+            Err(_) => {}
+        }
+        result
+    }
+
+    /// Send to each candidate in turn until one succeeds or fails with a non transient error
+    pub(crate) async fn failover<T, F, Fut>(&self, send: F) -> Result<T>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.failover_over(&self.candidates(), send).await
+    }
+
+    async fn failover_over<T, F, Fut>(&self, candidates: &[usize], send: F) -> Result<T>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let (last, others) = candidates.split_last().expect("at least one base path");
+        for &index in others {
+            match self.record(index, send(self.base_paths[index].clone()).await) {
+                Err(error) if error.is_transient() => continue,
+                result => return result,
+            }
+        }
+        self.record(*last, send(self.base_paths[*last].clone()).await)
+    }
+
+    /// Hedge when configured and a second base path is available, fail over otherwise
+    pub(crate) async fn hedged<T, F, Fut>(&self, send: F) -> Result<T>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let candidates = self.candidates();
+        let latency_budget = match self.policy.mode {
+            FailoverMode::Hedged { latency_budget } if candidates.len() > 1 => latency_budget,
+            _ => return self.failover_over(&candidates, send).await,
+        };
+        let (first, second) = (candidates[0], candidates[1]);
+
+        let primary = send(self.base_paths[first].clone());
+        tokio::pin!(primary);
+        tokio::select! {
+            result = &mut primary => {
+                return match self.record(first, result) {
+                    Err(error) if error.is_transient() => {
+                        self.failover_over(&candidates[1..], send).await
+                    }
+                    result => result,
+                };
+            }
+            _ = tokio::time::sleep(latency_budget) => {}
+        }
+
+        let hedge = send(self.base_paths[second].clone());
+        tokio::pin!(hedge);
+        tokio::select! {
+            result = &mut primary => match self.record(first, result) {
+                Ok(response) => Ok(response),
+                Err(_) => self.record(second, hedge.await),
+            },
+            result = &mut hedge => match self.record(second, result) {
+                Ok(response) => Ok(response),
+                Err(_) => self.record(first, primary.await),
+            },
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Staleness guard for quotes submitted to `swap` and `swap_instructions`
+//!
+
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::{quote::QuoteResponse, Result};
+
+/// The quote is older than the [`QuoteFreshness`] limits
+#[derive(Error, Debug, PartialEq)]
+#[error("stale quote, {slot_lag} slots behind, {}", age.map(|age| format!("received {age:?} ago")).unwrap_or_else(|| "received at an unknown time".into()))]
+pub struct StaleQuote {
+    pub slot_lag: u64,
+    /// `None` when the quote was not received by `quote`
+    pub age: Option<Duration>,
+}
+
+/// Maximum age of a quote, a limit set to `None` is not enforced
+#[derive(Clone, Debug)]
+pub struct QuoteFreshness {
+    /// Slots between `context_slot` and the latest slot given to [`QuoteFreshness::check`].
+    /// `swap` and `swap_instructions` give the highest `context_slot` the client has received,
+    /// so the lag is relative to the newer quotes, not to the chain
+    pub max_slot_lag: Option<u64>,
+    /// Wall-clock time since the quote was received by `quote`.
+    /// A quote without a reception time, e.g. deserialized by the caller, is rejected
+    pub max_age: Option<Duration>,
+}
+
+impl Default for QuoteFreshness {
+    fn default() -> Self {
+        Self {
+            max_slot_lag: Some(10),
+            max_age: Some(Duration::from_secs(2)),
+        }
+    }
+}
+
+impl QuoteFreshness {
+    /// Fail with [`StaleQuote`] if `quote_response` is older than a limit.
+    /// `latest_slot` is the newest slot known to the caller, e.g. from an RPC `getSlot`
+    pub fn check(&self, quote_response: &QuoteResponse, latest_slot: u64) -> Result<()> {
+        let slot_lag = latest_slot.saturating_sub(quote_response.context_slot);
+        let age = quote_response
+            .received_at()
+            .map(|received_at| received_at.elapsed());
+        let slot_stale = matches!(self.max_slot_lag, Some(max_slot_lag) if slot_lag > max_slot_lag);
+        let age_stale = match (self.max_age, age) {
+            (Some(max_age), Some(age)) => age > max_age,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        if slot_stale || age_stale {
+            return Err(StaleQuote { slot_lag, age }.into());
+        }
+        Ok(())
+    }
+}
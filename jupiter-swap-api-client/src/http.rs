@@ -0,0 +1,94 @@
+//! Transport independent representation of the requests sent to the API and of their responses
+//!
+
+use std::time::Duration;
+
+use reqwest::{header::RETRY_AFTER, Method, Response, StatusCode};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{endpoint::Endpoint, error::JupiterError, Result};
+
+#[derive(Debug)]
+pub(crate) struct ApiRequest {
+    pub endpoint: Endpoint,
+    /// Path of the endpoint, followed by its path parameters
+    pub path: String,
+    pub method: Method,
+    /// Encoded query string, without the leading `?`
+    pub query: Option<String>,
+    pub body: Option<serde_json::Value>,
+}
+
+impl ApiRequest {
+    pub fn new(endpoint: Endpoint, method: Method) -> Self {
+        Self {
+            endpoint,
+            path: endpoint.path().to_string(),
+            method,
+            query: None,
+            body: None,
+        }
+    }
+
+    pub fn get<Q: Serialize>(endpoint: Endpoint, query: &Q) -> Result<Self> {
+        Ok(Self {
+            query: Some(serde_qs::to_string(query)?),
+            ..Self::new(endpoint, Method::GET)
+        })
+    }
+
+    pub fn post<B: Serialize>(endpoint: Endpoint, body: &B) -> Result<Self> {
+        Ok(Self {
+            body: Some(serde_json::to_value(body).map_err(JupiterError::BodyEncoding)?),
+            ..Self::new(endpoint, Method::POST)
+        })
+    }
+
+    /// Append a path parameter, e.g. the mint of `/tokens/v1/token/{mint}`
+    pub fn path_segment(mut self, segment: &str) -> Self {
+        self.path.push('/');
+        self.path.push_str(segment);
+        self
+    }
+
+    pub fn url(&self, base_path: &str) -> String {
+        match &self.query {
+            Some(query) => format!("{base_path}{}?{query}", self.path),
+            None => format!("{base_path}{}", self.path),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ApiResponse {
+    pub status: StatusCode,
+    pub retry_after: Option<Duration>,
+    pub body: String,
+}
+
+impl ApiResponse {
+    pub async fn read(response: Response) -> Result<Self> {
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+            .map(Duration::from_secs);
+        Ok(Self {
+            status: response.status(),
+            retry_after,
+            body: response.text().await?,
+        })
+    }
+
+    /// Check the status code and deserialize the body
+    pub fn deserialize<T: DeserializeOwned>(self) -> Result<T> {
+        if !self.status.is_success() {
+            return Err(JupiterError::api(self.status, self.retry_after, self.body));
+        }
+        serde_json::from_str(&self.body).map_err(|source| JupiterError::Deserialize {
+            source,
+            body: self.body,
+        })
+    }
+}
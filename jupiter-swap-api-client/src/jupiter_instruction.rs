@@ -0,0 +1,615 @@
+//! Instructions of the Jupiter v6 aggregator program
+//!
+
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+};
+
+use thiserror::Error;
+
+use crate::Result;
+
+pub const JUPITER_PROGRAM_ID: Pubkey = pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+
+/// Instruction data or accounts that cannot be decoded as a Jupiter route instruction
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("invalid Jupiter instruction: {0}")]
+pub struct InvalidJupiterInstruction(pub &'static str);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JupiterInstructionKind {
+    Route,
+    RouteWithTokenLedger,
+    ExactOutRoute,
+    SharedAccountsRoute,
+    SharedAccountsRouteWithTokenLedger,
+    SharedAccountsExactOutRoute,
+}
+
+impl JupiterInstructionKind {
+    pub const ALL: [JupiterInstructionKind; 6] = [
+        Self::Route,
+        Self::RouteWithTokenLedger,
+        Self::ExactOutRoute,
+        Self::SharedAccountsRoute,
+        Self::SharedAccountsRouteWithTokenLedger,
+        Self::SharedAccountsExactOutRoute,
+    ];
+
+    /// Anchor discriminator, the first 8 bytes of `sha256("global:<instruction name>")`
+    pub fn discriminator(&self) -> [u8; 8] {
+        match self {
+            Self::Route => [229, 23, 203, 151, 122, 227, 173, 42],
+            Self::RouteWithTokenLedger => [150, 86, 71, 116, 167, 93, 14, 104],
+            Self::ExactOutRoute => [208, 51, 239, 151, 123, 43, 237, 92],
+            Self::SharedAccountsRoute => [193, 32, 155, 51, 65, 214, 156, 129],
+            Self::SharedAccountsRouteWithTokenLedger => [230, 121, 143, 80, 119, 159, 106, 170],
+            Self::SharedAccountsExactOutRoute => [176, 209, 105, 168, 154, 125, 69, 62],
+        }
+    }
+
+    pub fn from_data(data: &[u8]) -> Option<Self> {
+        let discriminator = data.get(..8)?;
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.discriminator() == discriminator)
+    }
+
+    pub fn is_exact_out(&self) -> bool {
+        matches!(
+            self,
+            Self::ExactOutRoute | Self::SharedAccountsExactOutRoute
+        )
+    }
+
+    /// The input amount is read from the token ledger instead of the instruction data
+    pub fn uses_token_ledger(&self) -> bool {
+        matches!(
+            self,
+            Self::RouteWithTokenLedger | Self::SharedAccountsRouteWithTokenLedger
+        )
+    }
+
+    /// Shared accounts routes start with the id of the program authority
+    pub fn is_shared_accounts(&self) -> bool {
+        matches!(
+            self,
+            Self::SharedAccountsRoute
+                | Self::SharedAccountsRouteWithTokenLedger
+                | Self::SharedAccountsExactOutRoute
+        )
+    }
+
+    /// Names of the accounts of the instruction, the swap accounts of each step follow them
+    pub fn account_names(&self) -> &'static [&'static str] {
+        match self {
+            Self::Route => &[
+                "token_program",
+                "user_transfer_authority",
+                "user_source_token_account",
+                "user_destination_token_account",
+                "destination_token_account",
+                "destination_mint",
+                "platform_fee_account",
+                "event_authority",
+                "program",
+            ],
+            Self::RouteWithTokenLedger => &[
+                "token_program",
+                "user_transfer_authority",
+                "user_source_token_account",
+                "user_destination_token_account",
+                "destination_token_account",
+                "destination_mint",
+                "platform_fee_account",
+                "token_ledger",
+                "event_authority",
+                "program",
+            ],
+            Self::ExactOutRoute => &[
+                "token_program",
+                "user_transfer_authority",
+                "user_source_token_account",
+                "user_destination_token_account",
+                "destination_token_account",
+                "source_mint",
+                "destination_mint",
+                "platform_fee_account",
+                "token_2022_program",
+                "event_authority",
+                "program",
+            ],
+            Self::SharedAccountsRoute | Self::SharedAccountsExactOutRoute => &[
+                "token_program",
+                "program_authority",
+                "user_transfer_authority",
+                "source_token_account",
+                "program_source_token_account",
+                "program_destination_token_account",
+                "destination_token_account",
+                "source_mint",
+                "destination_mint",
+                "platform_fee_account",
+                "token_2022_program",
+                "event_authority",
+                "program",
+            ],
+            Self::SharedAccountsRouteWithTokenLedger => &[
+                "token_program",
+                "program_authority",
+                "user_transfer_authority",
+                "source_token_account",
+                "program_source_token_account",
+                "program_destination_token_account",
+                "destination_token_account",
+                "source_mint",
+                "destination_mint",
+                "platform_fee_account",
+                "token_2022_program",
+                "token_ledger",
+                "event_authority",
+                "program",
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RouteAmounts {
+    ExactIn {
+        /// `None` with the token ledger
+        in_amount: Option<u64>,
+        quoted_out_amount: u64,
+    },
+    ExactOut {
+        out_amount: u64,
+        quoted_in_amount: u64,
+    },
+}
+
+/// Arguments following the route plan in the instruction data
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RouteArgs {
+    pub kind: JupiterInstructionKind,
+    pub amounts: RouteAmounts,
+    pub slippage_bps: u16,
+    pub platform_fee_bps: u8,
+}
+
+impl RouteArgs {
+    /// Decode the amounts at the end of a route instruction, `None` for other instructions
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let kind = JupiterInstructionKind::from_data(data)?;
+        let tail = data.get(data.len().checked_sub(Self::encoded_len(kind))?..)?;
+        let amount_count = Self::amount_count(kind);
+        let u64_at = |offset: usize| -> Option<u64> {
+            Some(u64::from_le_bytes(
+                tail.get(offset..offset + 8)?.try_into().ok()?,
+            ))
+        };
+        let amounts = match (kind.is_exact_out(), kind.uses_token_ledger()) {
+            (true, _) => RouteAmounts::ExactOut {
+                out_amount: u64_at(0)?,
+                quoted_in_amount: u64_at(8)?,
+            },
+            (false, true) => RouteAmounts::ExactIn {
+                in_amount: None,
+                quoted_out_amount: u64_at(0)?,
+            },
+            (false, false) => RouteAmounts::ExactIn {
+                in_amount: Some(u64_at(0)?),
+                quoted_out_amount: u64_at(8)?,
+            },
+        };
+        let slippage_offset = amount_count * 8;
+        Some(Self {
+            kind,
+            amounts,
+            slippage_bps: u16::from_le_bytes(
+                tail.get(slippage_offset..slippage_offset + 2)?
+                    .try_into()
+                    .ok()?,
+            ),
+            platform_fee_bps: *tail.get(slippage_offset + 2)?,
+        })
+    }
+}
+
+impl RouteArgs {
+    fn amount_count(kind: JupiterInstructionKind) -> usize {
+        if kind.uses_token_ledger() {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Length of the arguments following the route plan
+    fn encoded_len(kind: JupiterInstructionKind) -> usize {
+        Self::amount_count(kind) * 8 + 3
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut encoded = Vec::with_capacity(Self::encoded_len(self.kind));
+        match self.amounts {
+            RouteAmounts::ExactIn {
+                in_amount,
+                quoted_out_amount,
+            } => {
+                if let Some(in_amount) = in_amount {
+                    encoded.extend(in_amount.to_le_bytes());
+                }
+                encoded.extend(quoted_out_amount.to_le_bytes());
+            }
+            RouteAmounts::ExactOut {
+                out_amount,
+                quoted_in_amount,
+            } => {
+                encoded.extend(out_amount.to_le_bytes());
+                encoded.extend(quoted_in_amount.to_le_bytes());
+            }
+        }
+        encoded.extend(self.slippage_bps.to_le_bytes());
+        encoded.push(self.platform_fee_bps);
+        encoded
+    }
+
+    /// Rewrite the arguments at the end of `data`, e.g. to adjust the input amount of a composed swap.
+    /// The instruction kind and the amounts variant must be unchanged
+    pub fn patch(&self, data: &mut [u8]) -> Result<()> {
+        let encoded = self.encode();
+        let consistent = JupiterInstructionKind::from_data(data) == Some(self.kind)
+            && encoded.len() == Self::encoded_len(self.kind)
+            && self.kind.is_exact_out() == matches!(self.amounts, RouteAmounts::ExactOut { .. });
+        let start = data.len().checked_sub(encoded.len());
+        match start {
+            Some(start) if consistent && start >= 8 => {
+                data[start..].copy_from_slice(&encoded);
+                Ok(())
+            }
+            _ => Err(
+                InvalidJupiterInstruction("route arguments do not match the instruction").into(),
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    Bid,
+    Ask,
+}
+
+/// AMM swapped through by a step of the route plan, with its arguments
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Swap {
+    Saber,
+    SaberAddDecimalsDeposit,
+    SaberAddDecimalsWithdraw,
+    TokenSwap,
+    Sencha,
+    Step,
+    Cropper,
+    Raydium,
+    Crema {
+        a_to_b: bool,
+    },
+    Lifinity,
+    Mercurial,
+    Cykura,
+    Serum {
+        side: Side,
+    },
+    MarinadeDeposit,
+    MarinadeUnstake,
+    Aldrin {
+        side: Side,
+    },
+    AldrinV2 {
+        side: Side,
+    },
+    Whirlpool {
+        a_to_b: bool,
+    },
+    Invariant {
+        x_to_y: bool,
+    },
+    Meteora,
+    GooseFX,
+    DeltaFi {
+        stable: bool,
+    },
+    Balansol,
+    MarcoPolo {
+        x_to_y: bool,
+    },
+    Dradex {
+        side: Side,
+    },
+    LifinityV2,
+    RaydiumClmm,
+    Openbook {
+        side: Side,
+    },
+    Phoenix {
+        side: Side,
+    },
+    Symmetry {
+        from_token_id: u64,
+        to_token_id: u64,
+    },
+    TokenSwapV2,
+    HeliumTreasuryManagementRedeemV0,
+    StakeDexStakeWrappedSol,
+    StakeDexSwapViaStake {
+        bridge_stake_seed: u32,
+    },
+    GooseFXV2,
+    Perps,
+    PerpsAddLiquidity,
+    PerpsRemoveLiquidity,
+    MeteoraDlmm,
+    OpenBookV2 {
+        side: Side,
+    },
+    RaydiumClmmV2,
+    StakeDexPrefundWithdrawStakeAndDepositStake {
+        bridge_stake_seed: u32,
+    },
+    Clone {
+        pool_index: u8,
+        quantity_is_input: bool,
+        quantity_is_collateral: bool,
+    },
+    SanctumS {
+        src_lst_value_calc_accs: u8,
+        dst_lst_value_calc_accs: u8,
+        src_lst_index: u32,
+        dst_lst_index: u32,
+    },
+    SanctumSAddLiquidity {
+        lst_value_calc_accs: u8,
+        lst_index: u32,
+    },
+    SanctumSRemoveLiquidity {
+        lst_value_calc_accs: u8,
+        lst_index: u32,
+    },
+    RaydiumCP,
+}
+
+/// Step of the route plan encoded in the instruction, indexes refer to the token accounts of the route
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RouteStep {
+    pub swap: Swap,
+    pub percent: u8,
+    pub input_index: u8,
+    pub output_index: u8,
+}
+
+/// Borsh reader over instruction data
+struct Reader<'d> {
+    data: &'d [u8],
+}
+
+impl<'d> Reader<'d> {
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let bytes = self.data.get(..N)?.try_into().ok()?;
+        self.data = &self.data[N..];
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take::<1>().map(|[byte]| byte)
+    }
+
+    fn bool(&mut self) -> Option<bool> {
+        match self.u8()? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take().map(u64::from_le_bytes)
+    }
+
+    fn side(&mut self) -> Option<Side> {
+        match self.u8()? {
+            0 => Some(Side::Bid),
+            1 => Some(Side::Ask),
+            _ => None,
+        }
+    }
+
+    fn swap(&mut self) -> Option<Swap> {
+        Some(match self.u8()? {
+            0 => Swap::Saber,
+            1 => Swap::SaberAddDecimalsDeposit,
+            2 => Swap::SaberAddDecimalsWithdraw,
+            3 => Swap::TokenSwap,
+            4 => Swap::Sencha,
+            5 => Swap::Step,
+            6 => Swap::Cropper,
+            7 => Swap::Raydium,
+            8 => Swap::Crema {
+                a_to_b: self.bool()?,
+            },
+            9 => Swap::Lifinity,
+            10 => Swap::Mercurial,
+            11 => Swap::Cykura,
+            12 => Swap::Serum { side: self.side()? },
+            13 => Swap::MarinadeDeposit,
+            14 => Swap::MarinadeUnstake,
+            15 => Swap::Aldrin { side: self.side()? },
+            16 => Swap::AldrinV2 { side: self.side()? },
+            17 => Swap::Whirlpool {
+                a_to_b: self.bool()?,
+            },
+            18 => Swap::Invariant {
+                x_to_y: self.bool()?,
+            },
+            19 => Swap::Meteora,
+            20 => Swap::GooseFX,
+            21 => Swap::DeltaFi {
+                stable: self.bool()?,
+            },
+            22 => Swap::Balansol,
+            23 => Swap::MarcoPolo {
+                x_to_y: self.bool()?,
+            },
+            24 => Swap::Dradex { side: self.side()? },
+            25 => Swap::LifinityV2,
+            26 => Swap::RaydiumClmm,
+            27 => Swap::Openbook { side: self.side()? },
+            28 => Swap::Phoenix { side: self.side()? },
+            29 => Swap::Symmetry {
+                from_token_id: self.u64()?,
+                to_token_id: self.u64()?,
+            },
+            30 => Swap::TokenSwapV2,
+            31 => Swap::HeliumTreasuryManagementRedeemV0,
+            32 => Swap::StakeDexStakeWrappedSol,
+            33 => Swap::StakeDexSwapViaStake {
+                bridge_stake_seed: self.u32()?,
+            },
+            34 => Swap::GooseFXV2,
+            35 => Swap::Perps,
+            36 => Swap::PerpsAddLiquidity,
+            37 => Swap::PerpsRemoveLiquidity,
+            38 => Swap::MeteoraDlmm,
+            39 => Swap::OpenBookV2 { side: self.side()? },
+            40 => Swap::RaydiumClmmV2,
+            41 => Swap::StakeDexPrefundWithdrawStakeAndDepositStake {
+                bridge_stake_seed: self.u32()?,
+            },
+            42 => Swap::Clone {
+                pool_index: self.u8()?,
+                quantity_is_input: self.bool()?,
+                quantity_is_collateral: self.bool()?,
+            },
+            43 => Swap::SanctumS {
+                src_lst_value_calc_accs: self.u8()?,
+                dst_lst_value_calc_accs: self.u8()?,
+                src_lst_index: self.u32()?,
+                dst_lst_index: self.u32()?,
+            },
+            44 => Swap::SanctumSAddLiquidity {
+                lst_value_calc_accs: self.u8()?,
+                lst_index: self.u32()?,
+            },
+            45 => Swap::SanctumSRemoveLiquidity {
+                lst_value_calc_accs: self.u8()?,
+                lst_index: self.u32()?,
+            },
+            46 => Swap::RaydiumCP,
+            // Newer AMMs are not decoded, their arguments have unknown lengths
+            _ => return None,
+        })
+    }
+
+    fn route_plan(&mut self) -> Option<Vec<RouteStep>> {
+        let len = self.u32()?;
+        (0..len)
+            .map(|_| {
+                Some(RouteStep {
+                    swap: self.swap()?,
+                    percent: self.u8()?,
+                    input_index: self.u8()?,
+                    output_index: self.u8()?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Decoded data of a route instruction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RouteInstructionData {
+    /// Id of the program authority of shared accounts routes
+    pub id: Option<u8>,
+    /// `None` when a step swaps through an AMM unknown to this decoder
+    pub route_plan: Option<Vec<RouteStep>>,
+    pub args: RouteArgs,
+}
+
+impl RouteInstructionData {
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let args =
+            RouteArgs::decode(data).ok_or(InvalidJupiterInstruction("not a route instruction"))?;
+        let mut reader = Reader { data: &data[8..] };
+        let id = match args.kind.is_shared_accounts() {
+            true => Some(
+                reader
+                    .u8()
+                    .ok_or(InvalidJupiterInstruction("missing program authority id"))?,
+            ),
+            false => None,
+        };
+        let route_plan = reader
+            .route_plan()
+            // The route plan must be followed exactly by the arguments
+            .filter(|_| reader.data.len() == RouteArgs::encoded_len(args.kind));
+        Ok(Self {
+            id,
+            route_plan,
+            args,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NamedAccount {
+    pub name: &'static str,
+    pub account: AccountMeta,
+}
+
+/// Route instruction of the Jupiter program with its accounts
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JupiterInstruction {
+    pub data: RouteInstructionData,
+    pub accounts: Vec<NamedAccount>,
+    /// Accounts of the AMMs, in route plan order
+    pub remaining_accounts: Vec<AccountMeta>,
+}
+
+impl JupiterInstruction {
+    /// Decode `instruction`, e.g. `SwapInstructionsResponse::swap_instruction`
+    pub fn decode(instruction: &Instruction) -> Result<Self> {
+        if instruction.program_id != JUPITER_PROGRAM_ID {
+            return Err(InvalidJupiterInstruction("not a Jupiter program instruction").into());
+        }
+        let data = RouteInstructionData::decode(&instruction.data)?;
+        let names = data.args.kind.account_names();
+        if instruction.accounts.len() < names.len() {
+            return Err(InvalidJupiterInstruction("missing instruction accounts").into());
+        }
+        let (accounts, remaining_accounts) = instruction.accounts.split_at(names.len());
+        Ok(Self {
+            data,
+            accounts: names
+                .iter()
+                .zip(accounts)
+                .map(|(name, account)| NamedAccount {
+                    name,
+                    account: account.clone(),
+                })
+                .collect(),
+            remaining_accounts: remaining_accounts.to_vec(),
+        })
+    }
+
+    pub fn account(&self, name: &str) -> Option<&AccountMeta> {
+        self.accounts
+            .iter()
+            .find(|account| account.name == name)
+            .map(|account| &account.account)
+    }
+}
@@ -1,73 +1,446 @@
-use anyhow::{anyhow, Result};
+pub use builder::JupiterSwapApiClientBuilder;
+use cassette::{Cassette, CassetteMode};
+use dex::{DexCatalog, IndexedRouteMap, IndexedRouteMapRequest, ProgramIdToLabel};
+use endpoint::Endpoint;
+use error::JupiterError;
+use failover::{BasePaths, FailoverPolicy};
+use freshness::QuoteFreshness;
+use http::{ApiRequest, ApiResponse};
+use limit_order::{
+    CancelOrdersRequest, CancelOrdersResponse, CreateOrderRequest, CreateOrderResponse, OpenOrder,
+    OpenOrdersRequest, OrderHistoryRequest, OrderHistoryResponse,
+};
+use price::{PriceRequest, PriceResponse, TokenPrice};
 use quote::{QuoteRequest, QuoteResponse};
-use reqwest::{Client, Response};
+use rate_limit::RateLimiter;
+use recurring::{
+    CancelRecurringOrderRequest, CreateRecurringOrderRequest, DepositRecurringOrderRequest,
+    RecurringExecuteResponse, RecurringOrdersRequest, RecurringOrdersResponse,
+    RecurringTransactionResponse, WithdrawRecurringOrderRequest,
+};
+use reqwest::{Client, Method};
+use retry::{retry, RetryPolicy};
+pub use rust_decimal::Decimal;
 use serde::de::DeserializeOwned;
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
 use swap::{SwapInstructionsResponse, SwapInstructionsResponseInternal, SwapRequest, SwapResponse};
+use token::{TokenInfo, TokenTag, TradableMint};
+use ultra::{ExecuteRequest, ExecuteResponse, OrderRequest, OrderResponse};
 
+pub mod amount;
+pub mod assembler;
+mod builder;
+pub mod cassette;
+pub mod dex;
+pub mod endpoint;
+pub mod error;
+pub mod executor;
+pub mod failover;
+pub mod freshness;
+mod http;
+pub mod jupiter_instruction;
+pub mod limit_order;
+#[cfg(feature = "test-support")]
+pub mod mock_server;
+pub mod preflight;
+pub mod price;
+pub mod programs;
 pub mod quote;
-mod route_plan_with_metadata;
+pub mod rate_limit;
+pub mod recurring;
+pub mod retry;
+pub mod route_export;
+pub mod route_graph;
+pub mod route_plan_with_metadata;
+pub mod rpc;
 mod serde_helpers;
 pub mod swap;
+pub mod swap_result;
+pub mod token;
+pub mod transaction;
 pub mod transaction_config;
+pub mod ultra;
+pub mod verifier;
+
+/// Base path of the Jupiter APIs other than the swap API, such as the Price, Token, limit order, recurring order and Ultra APIs
+pub const DEFAULT_API_BASE_PATH: &str = "https://api.jup.ag";
 
 #[derive(Clone)]
 pub struct JupiterSwapApiClient {
-    pub base_path: String,
+    base_paths: Arc<BasePaths>,
+    api_base_path: String,
+    /// Shared so that connections and TLS sessions are reused across requests
+    client: Client,
+    retry_policy: Option<RetryPolicy>,
+    rate_limiter: Option<RateLimiter>,
+    cassette: Option<Arc<Cassette>>,
+    dex_catalog: Option<Arc<DexCatalog>>,
+    quote_freshness: Option<QuoteFreshness>,
+    /// Highest `context_slot` of the quotes received, shared by clones
+    latest_context_slot: Arc<AtomicU64>,
 }
 
-async fn check_is_success(response: Response) -> Result<Response> {
-    if !response.status().is_success() {
-        return Err(anyhow!(
-            "Request status not ok: {}, body: {:?}",
-            response.status(),
-            response.text().await
-        ));
-    }
-    Ok(response)
-}
-
-async fn check_status_code_and_deserialize<T: DeserializeOwned>(response: Response) -> Result<T> {
-    check_is_success(response)
-        .await?
-        .json::<T>()
-        .await
-        .map_err(Into::into)
-}
+pub type Result<T> = std::result::Result<T, JupiterError>;
 
 impl JupiterSwapApiClient {
     pub fn new(base_path: String) -> Self {
-        Self { base_path }
+        Self {
+            base_paths: Arc::new(BasePaths::new(vec![base_path], FailoverPolicy::default())),
+            api_base_path: DEFAULT_API_BASE_PATH.to_string(),
+            client: Client::new(),
+            retry_policy: None,
+            rate_limiter: None,
+            cassette: None,
+            dex_catalog: None,
+            quote_freshness: None,
+            latest_context_slot: Arc::default(),
+        }
+    }
+
+    pub fn builder(base_path: String) -> JupiterSwapApiClientBuilder {
+        JupiterSwapApiClientBuilder::new(base_path)
+    }
+
+    /// Primary base path
+    pub fn base_path(&self) -> &str {
+        self.base_paths.primary()
+    }
+
+    /// Primary base path followed by the fallbacks, in order
+    pub fn base_paths(&self) -> &[String] {
+        self.base_paths.all()
+    }
+
+    /// Base path of the Jupiter APIs other than the swap API
+    pub fn api_base_path(&self) -> &str {
+        &self.api_base_path
     }
 
     pub async fn quote(&self, quote_request: &QuoteRequest) -> Result<QuoteResponse> {
-        let query = serde_qs::to_string(&quote_request)?;
-        let response = Client::new()
-            .get(format!("{}/quote?{query}", self.base_path))
-            .send()
-            .await?;
-        check_status_code_and_deserialize(response).await
+        if let Some(dex_catalog) = &self.dex_catalog {
+            dex_catalog.validate_quote_request(quote_request)?;
+        }
+        let request = &ApiRequest::get(Endpoint::Quote, quote_request)?;
+        let mut quote_response: QuoteResponse = retry(self.retry_policy.as_ref(), true, || {
+            self.base_paths
+                .hedged(|base_path| self.send(base_path, request))
+        })
+        .await?;
+        quote_response.received_at = Some(Instant::now());
+        self.latest_context_slot
+            .fetch_max(quote_response.context_slot, Ordering::Relaxed);
+        Ok(quote_response)
+    }
+
+    /// Highest `context_slot` of the quotes received by this client and its clones
+    pub fn latest_context_slot(&self) -> u64 {
+        self.latest_context_slot.load(Ordering::Relaxed)
+    }
+
+    /// Check `quote_response` against the freshness policy, using the latest slot seen by the client
+    pub fn check_quote_freshness(&self, quote_response: &QuoteResponse) -> Result<()> {
+        match &self.quote_freshness {
+            Some(quote_freshness) => {
+                quote_freshness.check(quote_response, self.latest_context_slot())
+            }
+            None => Ok(()),
+        }
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        base_path: String,
+        request: &ApiRequest,
+    ) -> Result<T> {
+        self.exchange(&base_path, request).await?.deserialize()
+    }
+
+    /// Send `request`, or replay it from the cassette
+    async fn exchange(&self, base_path: &str, request: &ApiRequest) -> Result<ApiResponse> {
+        if let Some(cassette) = &self.cassette {
+            if cassette.mode() == CassetteMode::Replay {
+                return cassette.replay_request(request);
+            }
+        }
+        self.acquire_permit(request.endpoint).await?;
+        let mut request_builder = self
+            .client
+            .request(request.method.clone(), request.url(base_path));
+        if let Some(body) = &request.body {
+            request_builder = request_builder.json(body);
+        }
+        let response = ApiResponse::read(request_builder.send().await?).await?;
+        if let Some(cassette) = &self.cassette {
+            cassette.record_interaction(request, &response);
+        }
+        Ok(response)
+    }
+
+    /// Write the interactions recorded by the cassette to its file, see [`Cassette::save`]
+    pub async fn save_cassette(&self) -> Result<()> {
+        match &self.cassette {
+            Some(cassette) if cassette.mode() == CassetteMode::Record => cassette.save().await,
+            _ => Ok(()),
+        }
+    }
+
+    async fn acquire_permit(&self, endpoint: Endpoint) -> Result<()> {
+        match &self.rate_limiter {
+            Some(rate_limiter) => rate_limiter.acquire(endpoint).await,
+            None => Ok(()),
+        }
+    }
+
+    fn retry_swap(&self) -> bool {
+        matches!(&self.retry_policy, Some(policy) if policy.retry_swap)
     }
 
     pub async fn swap(&self, swap_request: &SwapRequest) -> Result<SwapResponse> {
-        let response = Client::new()
-            .post(format!("{}/swap", self.base_path))
-            .json(swap_request)
-            .send()
-            .await?;
-        check_status_code_and_deserialize(response).await
+        self.check_quote_freshness(&swap_request.quote_response)?;
+        let request = &ApiRequest::post(Endpoint::Swap, swap_request)?;
+        retry(self.retry_policy.as_ref(), self.retry_swap(), || {
+            self.base_paths
+                .failover(|base_path| self.send(base_path, request))
+        })
+        .await
     }
 
     pub async fn swap_instructions(
         &self,
         swap_request: &SwapRequest,
     ) -> Result<SwapInstructionsResponse> {
-        let response = Client::new()
-            .post(format!("{}/swap-instructions", self.base_path))
-            .json(swap_request)
-            .send()
-            .await?;
-        check_status_code_and_deserialize::<SwapInstructionsResponseInternal>(response)
-            .await
-            .map(Into::into)
+        self.check_quote_freshness(&swap_request.quote_response)?;
+        let request = &ApiRequest::post(Endpoint::SwapInstructions, swap_request)?;
+        retry(self.retry_policy.as_ref(), self.retry_swap(), || {
+            self.base_paths.failover(|base_path| {
+                self.send::<SwapInstructionsResponseInternal>(base_path, request)
+            })
+        })
+        .await
+        .map(Into::into)
+    }
+
+    /// Label of every DEX program the API routes through
+    pub async fn program_id_to_label(&self) -> Result<ProgramIdToLabel> {
+        let request = &ApiRequest::new(Endpoint::ProgramIdToLabel, Method::GET);
+        retry(self.retry_policy.as_ref(), true, || {
+            self.base_paths
+                .failover(|base_path| self.send(base_path, request))
+        })
+        .await
+    }
+
+    pub async fn dex_catalog(&self) -> Result<DexCatalog> {
+        self.program_id_to_label().await.map(Into::into)
+    }
+
+    pub async fn indexed_route_map(
+        &self,
+        indexed_route_map_request: &IndexedRouteMapRequest,
+    ) -> Result<IndexedRouteMap> {
+        let request = &ApiRequest::get(Endpoint::IndexedRouteMap, indexed_route_map_request)?;
+        retry(self.retry_policy.as_ref(), true, || {
+            self.base_paths
+                .failover(|base_path| self.send(base_path, request))
+        })
+        .await
+    }
+
+    pub async fn price(&self, price_request: &PriceRequest) -> Result<PriceResponse> {
+        let request = &ApiRequest::get(Endpoint::Price, price_request)?;
+        retry(self.retry_policy.as_ref(), true, || {
+            self.send(self.api_base_path.clone(), request)
+        })
+        .await
+    }
+
+    /// Price of a single token in USDC, `None` when it has no reliable price
+    pub async fn token_price(&self, mint: &Pubkey) -> Result<Option<TokenPrice>> {
+        let mut price_response = self.price(&PriceRequest::new(vec![*mint])).await?;
+        Ok(price_response.data.remove(mint).flatten())
+    }
+
+    /// Metadata of `mint`, `None` when the token is not known to Jupiter
+    pub async fn token(&self, mint: &Pubkey) -> Result<Option<TokenInfo>> {
+        let request =
+            &ApiRequest::new(Endpoint::Token, Method::GET).path_segment(&mint.to_string());
+        retry(self.retry_policy.as_ref(), true, || {
+            self.send(self.api_base_path.clone(), request)
+        })
+        .await
+    }
+
+    /// Tokens having any of `tags`
+    pub async fn tagged_tokens(&self, tags: &[TokenTag]) -> Result<Vec<TokenInfo>> {
+        let tags = tags
+            .iter()
+            .map(TokenTag::as_str)
+            .collect::<Vec<_>>()
+            .join(",");
+        let request = &ApiRequest::new(Endpoint::TaggedTokens, Method::GET).path_segment(&tags);
+        retry(self.retry_policy.as_ref(), true, || {
+            self.send(self.api_base_path.clone(), request)
+        })
+        .await
+    }
+
+    /// Mints of every token tradable on Jupiter
+    pub async fn tradable_mints(&self) -> Result<Vec<Pubkey>> {
+        let request = &ApiRequest::new(Endpoint::TradableTokens, Method::GET);
+        let mints: Vec<TradableMint> = retry(self.retry_policy.as_ref(), true, || {
+            self.send(self.api_base_path.clone(), request)
+        })
+        .await?;
+        Ok(mints.into_iter().map(|TradableMint(mint)| mint).collect())
+    }
+
+    /// Unsigned transaction creating a limit order
+    pub async fn create_limit_order(
+        &self,
+        create_order_request: &CreateOrderRequest,
+    ) -> Result<CreateOrderResponse> {
+        let request = &ApiRequest::post(Endpoint::CreateLimitOrder, create_order_request)?;
+        retry(self.retry_policy.as_ref(), self.retry_swap(), || {
+            self.send(self.api_base_path.clone(), request)
+        })
+        .await
+    }
+
+    /// Unsigned transactions cancelling the given orders, or every open order of the maker
+    pub async fn cancel_limit_orders(
+        &self,
+        cancel_orders_request: &CancelOrdersRequest,
+    ) -> Result<CancelOrdersResponse> {
+        let request = &ApiRequest::post(Endpoint::CancelLimitOrders, cancel_orders_request)?;
+        retry(self.retry_policy.as_ref(), self.retry_swap(), || {
+            self.send(self.api_base_path.clone(), request)
+        })
+        .await
+    }
+
+    pub async fn open_limit_orders(
+        &self,
+        open_orders_request: &OpenOrdersRequest,
+    ) -> Result<Vec<OpenOrder>> {
+        let request = &ApiRequest::get(Endpoint::OpenLimitOrders, open_orders_request)?;
+        retry(self.retry_policy.as_ref(), true, || {
+            self.send(self.api_base_path.clone(), request)
+        })
+        .await
+    }
+
+    /// Filled, cancelled and open orders of a wallet, most recent first
+    pub async fn limit_order_history(
+        &self,
+        order_history_request: &OrderHistoryRequest,
+    ) -> Result<OrderHistoryResponse> {
+        let request = &ApiRequest::get(Endpoint::LimitOrderHistory, order_history_request)?;
+        retry(self.retry_policy.as_ref(), true, || {
+            self.send(self.api_base_path.clone(), request)
+        })
+        .await
+    }
+
+    /// Unsigned transaction creating a recurring order
+    pub async fn create_recurring_order(
+        &self,
+        create_recurring_order_request: &CreateRecurringOrderRequest,
+    ) -> Result<RecurringTransactionResponse> {
+        self.recurring_transaction(
+            Endpoint::CreateRecurringOrder,
+            create_recurring_order_request,
+        )
+        .await
+    }
+
+    /// Unsigned transaction closing a recurring order and returning the remaining tokens
+    pub async fn cancel_recurring_order(
+        &self,
+        cancel_recurring_order_request: &CancelRecurringOrderRequest,
+    ) -> Result<RecurringTransactionResponse> {
+        self.recurring_transaction(
+            Endpoint::CancelRecurringOrder,
+            cancel_recurring_order_request,
+        )
+        .await
+    }
+
+    pub async fn deposit_recurring_order(
+        &self,
+        deposit_recurring_order_request: &DepositRecurringOrderRequest,
+    ) -> Result<RecurringTransactionResponse> {
+        self.recurring_transaction(
+            Endpoint::DepositRecurringOrder,
+            deposit_recurring_order_request,
+        )
+        .await
+    }
+
+    pub async fn withdraw_recurring_order(
+        &self,
+        withdraw_recurring_order_request: &WithdrawRecurringOrderRequest,
+    ) -> Result<RecurringTransactionResponse> {
+        self.recurring_transaction(
+            Endpoint::WithdrawRecurringOrder,
+            withdraw_recurring_order_request,
+        )
+        .await
+    }
+
+    async fn recurring_transaction<B: serde::Serialize>(
+        &self,
+        endpoint: Endpoint,
+        body: &B,
+    ) -> Result<RecurringTransactionResponse> {
+        let request = &ApiRequest::post(endpoint, body)?;
+        retry(self.retry_policy.as_ref(), self.retry_swap(), || {
+            self.send(self.api_base_path.clone(), request)
+        })
+        .await
+    }
+
+    /// Send a recurring order transaction signed by the user, with the `request_id` it was built with.
+    /// Never retried, the transaction may have landed even if the response was lost
+    pub async fn recurring_execute(
+        &self,
+        execute_request: &ExecuteRequest,
+    ) -> Result<RecurringExecuteResponse> {
+        let request = &ApiRequest::post(Endpoint::RecurringExecute, execute_request)?;
+        self.send(self.api_base_path.clone(), request).await
+    }
+
+    /// Active or past recurring orders of a user, with their fills
+    pub async fn recurring_orders(
+        &self,
+        recurring_orders_request: &RecurringOrdersRequest,
+    ) -> Result<RecurringOrdersResponse> {
+        let request = &ApiRequest::get(Endpoint::RecurringOrders, recurring_orders_request)?;
+        retry(self.retry_policy.as_ref(), true, || {
+            self.send(self.api_base_path.clone(), request)
+        })
+        .await
+    }
+
+    /// Ultra order, with an unsigned transaction when `taker` is set
+    pub async fn ultra_order(&self, order_request: &OrderRequest) -> Result<OrderResponse> {
+        let request = &ApiRequest::get(Endpoint::UltraOrder, order_request)?;
+        retry(self.retry_policy.as_ref(), true, || {
+            self.send(self.api_base_path.clone(), request)
+        })
+        .await
+    }
+
+    /// Send a signed Ultra order, a failed execution is returned as `ExecuteStatus::Failed`.
+    /// Never retried, the transaction may have landed even if the response was lost
+    pub async fn ultra_execute(&self, execute_request: &ExecuteRequest) -> Result<ExecuteResponse> {
+        let request = &ApiRequest::post(Endpoint::UltraExecute, execute_request)?;
+        self.send(self.api_base_path.clone(), request).await
     }
 }
@@ -0,0 +1,256 @@
+//! Limit order API data structures, orders are filled by keepers of the limit order program
+//!
+
+use serde::{Deserialize, Serialize, Serializer};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::{
+    serde_helpers::{field_as_string, option_field_as_string, option_field_as_string_or_auto},
+    transaction::EncodedTransaction,
+};
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOrderRequest {
+    #[serde(with = "field_as_string")]
+    pub input_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub output_mint: Pubkey,
+    /// Owner of the order, receiving the output tokens
+    #[serde(with = "field_as_string")]
+    pub maker: Pubkey,
+    /// Fee payer, usually the maker
+    #[serde(with = "field_as_string")]
+    pub payer: Pubkey,
+    pub params: CreateOrderParams,
+    /// Compute unit price in micro lamports, picked by the API when `None`
+    #[serde(with = "option_field_as_string_or_auto")]
+    pub compute_unit_price: Option<u64>,
+    /// Referral account collecting `params.fee_bps`
+    #[serde(
+        with = "option_field_as_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub referral: Option<Pubkey>,
+    /// Required for Token-2022 input mints
+    #[serde(
+        with = "option_field_as_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub input_token_program: Option<Pubkey>,
+    /// Required for Token-2022 output mints
+    #[serde(
+        with = "option_field_as_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub output_token_program: Option<Pubkey>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wrap_and_unwrap_sol: Option<bool>,
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOrderParams {
+    /// Input amount, in base units
+    #[serde(with = "field_as_string")]
+    pub making_amount: u64,
+    /// Minimum output amount, in base units
+    #[serde(with = "field_as_string")]
+    pub taking_amount: u64,
+    /// Unix timestamp in seconds after which the order can no longer be filled
+    #[serde(
+        with = "option_field_as_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub expired_at: Option<i64>,
+    /// Referral fee in basis points
+    #[serde(
+        with = "option_field_as_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub fee_bps: Option<u16>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOrderResponse {
+    /// Order account created by the transaction
+    #[serde(with = "field_as_string")]
+    pub order: Pubkey,
+    /// Unsigned transaction, to be signed by the maker and the payer
+    pub tx: EncodedTransaction,
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelOrdersRequest {
+    #[serde(with = "field_as_string")]
+    pub maker: Pubkey,
+    /// Compute unit price in micro lamports, picked by the API when `None`
+    #[serde(with = "option_field_as_string_or_auto")]
+    pub compute_unit_price: Option<u64>,
+    /// Orders to cancel, every open order of the maker when empty
+    #[serde(
+        serialize_with = "serialize_orders",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub orders: Vec<Pubkey>,
+}
+
+fn serialize_orders<S: Serializer>(orders: &[Pubkey], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(orders.iter().map(Pubkey::to_string))
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelOrdersResponse {
+    /// Unsigned transactions, several orders are cancelled per transaction
+    pub txs: Vec<EncodedTransaction>,
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenOrdersRequest {
+    #[serde(with = "field_as_string")]
+    pub wallet: Pubkey,
+    #[serde(
+        with = "option_field_as_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub input_mint: Option<Pubkey>,
+    #[serde(
+        with = "option_field_as_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub output_mint: Option<Pubkey>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenOrder {
+    #[serde(with = "field_as_string")]
+    pub public_key: Pubkey,
+    pub account: OrderAccount,
+}
+
+/// Order account of the limit order program, amounts are in base units
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderAccount {
+    #[serde(with = "field_as_string")]
+    pub maker: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub input_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub output_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub input_token_program: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub output_token_program: Pubkey,
+    /// Escrow holding the remaining input tokens
+    #[serde(with = "field_as_string")]
+    pub input_mint_reserve: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub fee_account: Pubkey,
+    pub fee_bps: u16,
+    /// Remaining input amount
+    #[serde(with = "field_as_string")]
+    pub making_amount: u64,
+    /// Remaining output amount
+    #[serde(with = "field_as_string")]
+    pub taking_amount: u64,
+    #[serde(with = "field_as_string")]
+    pub ori_making_amount: u64,
+    #[serde(with = "field_as_string")]
+    pub ori_taking_amount: u64,
+    #[serde(with = "field_as_string")]
+    pub borrow_making_amount: u64,
+    /// RFC 3339 timestamps
+    pub expired_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub unique_id: String,
+    pub bump: u8,
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderHistoryRequest {
+    #[serde(with = "field_as_string")]
+    pub wallet: Pubkey,
+    /// Page number, starting at 1
+    pub page: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderHistoryResponse {
+    pub orders: Vec<HistoricalOrder>,
+    pub has_more_data: bool,
+    pub page: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum OrderStatus {
+    Open,
+    Completed,
+    Cancelled,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Order and its fills, `raw_` amounts are in base units and the others in human readable units
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoricalOrder {
+    #[serde(with = "field_as_string")]
+    pub user_pubkey: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub order_key: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub input_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub output_mint: Pubkey,
+    pub making_amount: String,
+    pub taking_amount: String,
+    pub remaining_making_amount: String,
+    pub remaining_taking_amount: String,
+    #[serde(with = "field_as_string")]
+    pub raw_making_amount: u64,
+    #[serde(with = "field_as_string")]
+    pub raw_taking_amount: u64,
+    #[serde(with = "field_as_string")]
+    pub raw_remaining_making_amount: u64,
+    #[serde(with = "field_as_string")]
+    pub raw_remaining_taking_amount: u64,
+    pub expired_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub status: OrderStatus,
+    #[serde(with = "field_as_string")]
+    pub open_tx: Signature,
+    #[serde(default, with = "option_field_as_string")]
+    pub close_tx: Option<Signature>,
+    #[serde(default)]
+    pub trades: Vec<OrderTrade>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderTrade {
+    #[serde(with = "field_as_string")]
+    pub keeper: Pubkey,
+    pub input_amount: String,
+    pub output_amount: String,
+    #[serde(with = "field_as_string")]
+    pub raw_input_amount: u64,
+    #[serde(with = "field_as_string")]
+    pub raw_output_amount: u64,
+    #[serde(with = "field_as_string")]
+    pub fee_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub raw_fee_amount: u64,
+    #[serde(with = "field_as_string")]
+    pub tx_id: Signature,
+    pub confirmed_at: String,
+}
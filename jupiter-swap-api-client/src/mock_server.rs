@@ -0,0 +1,232 @@
+//! Local mock of the Jupiter API for offline integration tests, enabled with the `test-support` feature
+//!
+
+use std::{
+    collections::{HashMap, VecDeque},
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use hyper::{
+    header::{CONTENT_TYPE, RETRY_AFTER},
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server, StatusCode,
+};
+use serde::Serialize;
+use tokio::sync::oneshot;
+
+use crate::endpoint::Endpoint;
+
+/// Scripted response of the mock server
+#[derive(Clone, Debug)]
+pub enum MockResponse {
+    /// 200 with a JSON body
+    Json(serde_json::Value),
+    /// Any status, e.g. a 429 with `Retry-After` or a 500
+    Status {
+        status: u16,
+        body: String,
+        retry_after: Option<u64>,
+    },
+    /// 200 with a body that is not valid JSON
+    Malformed(String),
+    /// Respond after a delay
+    Delayed(Duration, Box<MockResponse>),
+}
+
+impl MockResponse {
+    /// 200 with `value` serialized as JSON, e.g. a `QuoteResponse` or a `SwapResponse`
+    pub fn json<T: Serialize>(value: &T) -> Self {
+        Self::Json(serde_json::to_value(value).expect("fixture serializes to JSON"))
+    }
+
+    pub fn status(status: u16, body: impl Into<String>) -> Self {
+        Self::Status {
+            status,
+            body: body.into(),
+            retry_after: None,
+        }
+    }
+
+    /// 429 Too Many Requests
+    pub fn rate_limited(retry_after: Option<u64>) -> Self {
+        Self::Status {
+            status: 429,
+            body: r#"{"error":"Too many requests"}"#.into(),
+            retry_after,
+        }
+    }
+
+    /// Jupiter error body with an `errorCode`, e.g. `COULD_NOT_FIND_ANY_ROUTE`
+    pub fn error_code(status: u16, error_code: &str) -> Self {
+        Self::status(
+            status,
+            serde_json::json!({ "error": error_code, "errorCode": error_code }).to_string(),
+        )
+    }
+
+    pub fn delayed(delay: Duration, response: MockResponse) -> Self {
+        Self::Delayed(delay, Box::new(response))
+    }
+}
+
+/// Request received by the mock server
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    /// Query string, without the leading `?`
+    pub query: Option<String>,
+    pub body: String,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Scripted responses per path, served in order
+    responses: HashMap<String, VecDeque<MockResponse>>,
+    /// Served once the scripted responses of a path are exhausted
+    default_responses: HashMap<String, MockResponse>,
+    requests: Vec<RecordedRequest>,
+}
+
+/// Serves scripted responses on a local port, stopped when dropped
+pub struct MockJupiterServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl MockJupiterServer {
+    /// Start the server on a random local port, requires a tokio runtime
+    pub fn start() -> Self {
+        let state = Arc::new(Mutex::new(State::default()));
+        let service_state = state.clone();
+        let make_service = make_service_fn(move |_| {
+            let state = service_state.clone();
+            async move { Ok::<_, Infallible>(service_fn(move |request| handle(state.clone(), request))) }
+        });
+        let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service);
+        let addr = server.local_addr();
+        let (shutdown, shutdown_receiver) = oneshot::channel();
+        tokio::spawn(server.with_graceful_shutdown(async {
+            shutdown_receiver.await.ok();
+        }));
+        Self {
+            addr,
+            state,
+            shutdown: Some(shutdown),
+        }
+    }
+
+    /// Base path to give to the client
+    pub fn base_path(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Queue `response` for the next request to `endpoint`
+    pub fn push_response(&self, endpoint: Endpoint, response: MockResponse) {
+        self.push_response_for_path(endpoint.path(), response);
+    }
+
+    /// Queue `response` for the next request to `path`, for paths not covered by [`Endpoint`]
+    pub fn push_response_for_path(&self, path: &str, response: MockResponse) {
+        self.state
+            .lock()
+            .unwrap()
+            .responses
+            .entry(path.to_string())
+            .or_default()
+            .push_back(response);
+    }
+
+    /// Response for `endpoint` once its queued responses are exhausted
+    pub fn set_default_response(&self, endpoint: Endpoint, response: MockResponse) {
+        self.state
+            .lock()
+            .unwrap()
+            .default_responses
+            .insert(endpoint.path().to_string(), response);
+    }
+
+    /// Requests received so far, in order
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    pub fn requests_to(&self, endpoint: Endpoint) -> Vec<RecordedRequest> {
+        self.requests()
+            .into_iter()
+            .filter(|request| request.path == endpoint.path())
+            .collect()
+    }
+}
+
+impl Drop for MockJupiterServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+    }
+}
+
+async fn handle(
+    state: Arc<Mutex<State>>,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    let query = request.uri().query().map(str::to_string);
+    let body = hyper::body::to_bytes(request.into_body())
+        .await
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+        .unwrap_or_default();
+
+    let response = {
+        let mut state = state.lock().unwrap();
+        state.requests.push(RecordedRequest {
+            method,
+            path: path.clone(),
+            query,
+            body,
+        });
+        state
+            .responses
+            .get_mut(&path)
+            .and_then(VecDeque::pop_front)
+            .or_else(|| state.default_responses.get(&path).cloned())
+    };
+
+    Ok(match response {
+        Some(response) => respond(response).await,
+        None => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::from(format!("no scripted response for {path}")))
+            .unwrap(),
+    })
+}
+
+async fn respond(mut response: MockResponse) -> Response<Body> {
+    while let MockResponse::Delayed(delay, inner) = response {
+        tokio::time::sleep(delay).await;
+        response = *inner;
+    }
+    let builder = Response::builder().header(CONTENT_TYPE, "application/json");
+    match response {
+        MockResponse::Json(value) => builder.body(Body::from(value.to_string())),
+        MockResponse::Status {
+            status,
+            body,
+            retry_after,
+        } => match retry_after {
+            Some(retry_after) => builder.header(RETRY_AFTER, retry_after),
+            None => builder,
+        }
+        .status(status)
+        .body(Body::from(body)),
+        MockResponse::Malformed(body) => builder.body(Body::from(body)),
+        MockResponse::Delayed(..) => unreachable!("delays are unwrapped above"),
+    }
+    .unwrap()
+}
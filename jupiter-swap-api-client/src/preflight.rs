@@ -0,0 +1,347 @@
+//! Simulate a built swap transaction before sending it, and size its compute unit limit
+//!
+
+use std::collections::HashMap;
+
+use solana_sdk::{
+    account::Account,
+    compute_budget::{self, ComputeBudgetInstruction},
+    instruction::InstructionError,
+    message::VersionedMessage,
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::{TransactionError, VersionedTransaction},
+};
+use thiserror::Error;
+
+use crate::{
+    executor::ExecutionError,
+    jupiter_instruction::JUPITER_PROGRAM_ID,
+    programs::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID},
+    rpc::SolanaRpc,
+    transaction::SwapTransactionError,
+    Result,
+};
+
+/// Maximum compute unit limit of a transaction
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Size of an SPL token account, Token-2022 extensions follow it
+const TOKEN_ACCOUNT_SIZE: usize = 165;
+/// Account type of Token-2022 accounts with extensions
+const TOKEN_ACCOUNT_TYPE: u8 = 2;
+/// Borsh tag of `ComputeBudgetInstruction::SetComputeUnitLimit`
+const SET_COMPUTE_UNIT_LIMIT_TAG: u8 = 2;
+
+/// Custom errors of the Jupiter aggregator program
+#[derive(Error, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JupiterProgramError {
+    #[error("empty route")]
+    EmptyRoute,
+    #[error("slippage tolerance exceeded")]
+    SlippageToleranceExceeded,
+    #[error("invalid calculation")]
+    InvalidCalculation,
+    #[error("missing platform fee account")]
+    MissingPlatformFeeAccount,
+    #[error("invalid slippage")]
+    InvalidSlippage,
+    #[error("not enough percent to 100")]
+    NotEnoughPercent,
+    #[error("token input index is invalid")]
+    InvalidInputIndex,
+    #[error("token output index is invalid")]
+    InvalidOutputIndex,
+    #[error("not enough account keys")]
+    NotEnoughAccountKeys,
+    #[error("non zero minimum out amount not supported")]
+    NonZeroMinimumOutAmountNotSupported,
+    #[error("invalid route plan")]
+    InvalidRoutePlan,
+    #[error("invalid referral authority")]
+    InvalidReferralAuthority,
+    #[error("token account doesn't match the ledger")]
+    LedgerTokenAccountDoesNotMatch,
+    #[error("invalid token ledger")]
+    InvalidTokenLedger,
+    #[error("token program ID is invalid")]
+    IncorrectTokenProgramID,
+    #[error("token program not provided")]
+    TokenProgramNotProvided,
+    #[error("swap not supported")]
+    SwapNotSupported,
+    #[error("exact out amount doesn't match")]
+    ExactOutAmountNotMatched,
+    #[error("source mint and destination mint cannot the same")]
+    SourceAndDestinationMintCannotBeTheSame,
+}
+
+impl JupiterProgramError {
+    pub const ALL: [Self; 19] = [
+        Self::EmptyRoute,
+        Self::SlippageToleranceExceeded,
+        Self::InvalidCalculation,
+        Self::MissingPlatformFeeAccount,
+        Self::InvalidSlippage,
+        Self::NotEnoughPercent,
+        Self::InvalidInputIndex,
+        Self::InvalidOutputIndex,
+        Self::NotEnoughAccountKeys,
+        Self::NonZeroMinimumOutAmountNotSupported,
+        Self::InvalidRoutePlan,
+        Self::InvalidReferralAuthority,
+        Self::LedgerTokenAccountDoesNotMatch,
+        Self::InvalidTokenLedger,
+        Self::IncorrectTokenProgramID,
+        Self::TokenProgramNotProvided,
+        Self::SwapNotSupported,
+        Self::ExactOutAmountNotMatched,
+        Self::SourceAndDestinationMintCannotBeTheSame,
+    ];
+
+    /// Custom error code, starting at anchor's 6000
+    pub fn code(&self) -> u32 {
+        6000 + *self as u32
+    }
+
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|error| error.code() == code)
+    }
+}
+
+/// Custom error returned by the program of a failed instruction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProgramError {
+    pub instruction_index: u8,
+    pub program_id: Option<Pubkey>,
+    pub code: u32,
+    /// Set when the program is the Jupiter aggregator and the code is known
+    pub jupiter_error: Option<JupiterProgramError>,
+}
+
+/// Token balance of one of the user's token accounts before and after the simulation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenBalanceDelta {
+    pub account: Pubkey,
+    pub mint: Pubkey,
+    /// Zero when the account is created by the transaction
+    pub pre_amount: u64,
+    /// Zero when the account is closed by the transaction
+    pub post_amount: u64,
+}
+
+impl TokenBalanceDelta {
+    pub fn change(&self) -> i128 {
+        self.post_amount as i128 - self.pre_amount as i128
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimulationReport {
+    /// Error the transaction failed with, if any
+    pub err: Option<TransactionError>,
+    pub program_error: Option<ProgramError>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+    /// Change of the user's lamports, transaction fee included.
+    /// `None` when the simulation returned no accounts, e.g. because it failed
+    pub lamport_change: Option<i128>,
+    /// Token accounts owned by the user among the writable accounts of the transaction
+    pub token_balance_deltas: Vec<TokenBalanceDelta>,
+}
+
+impl SimulationReport {
+    /// Fail with `SimulationFailed` if the simulation failed
+    pub fn into_result(self) -> Result<Self> {
+        match self.err {
+            Some(err) => Err(ExecutionError::SimulationFailed {
+                err,
+                logs: self.logs,
+            }
+            .into()),
+            None => Ok(self),
+        }
+    }
+
+    /// Change of the balance of `mint` over the user's token accounts
+    pub fn token_balance_change(&self, mint: &Pubkey) -> i128 {
+        self.token_balance_deltas
+            .iter()
+            .filter(|delta| &delta.mint == mint)
+            .map(TokenBalanceDelta::change)
+            .sum()
+    }
+
+    /// Consumed units increased by `margin_bps`, capped at `MAX_COMPUTE_UNIT_LIMIT`
+    pub fn compute_unit_limit(&self, margin_bps: u16) -> Option<u32> {
+        let units = self.units_consumed? as u128 * (10_000 + margin_bps as u128) / 10_000;
+        Some(units.min(MAX_COMPUTE_UNIT_LIMIT as u128) as u32)
+    }
+}
+
+/// Simulate `transaction` and report its effect on the accounts of `user_public_key`.
+/// Signatures are not verified, so the transaction may be simulated before being signed
+pub async fn simulate<R: SolanaRpc + ?Sized>(
+    rpc: &R,
+    transaction: &VersionedTransaction,
+    user_public_key: &Pubkey,
+) -> Result<SimulationReport> {
+    let mut addresses = vec![*user_public_key];
+    for address in writable_accounts(rpc, &transaction.message).await? {
+        if !addresses.contains(&address) {
+            addresses.push(address);
+        }
+    }
+    let pre_accounts = rpc.get_accounts(&addresses).await?;
+    let simulation = rpc.simulate_transaction(transaction, &addresses).await?;
+
+    let lamports =
+        |account: &Option<Account>| account.as_ref().map_or(0, |account| account.lamports);
+    let lamport_change = match (pre_accounts.first(), simulation.accounts.first()) {
+        (Some(pre_account), Some(post_account)) => {
+            Some(lamports(post_account) as i128 - lamports(pre_account) as i128)
+        }
+        _ => None,
+    };
+    let token_balance_deltas = addresses
+        .iter()
+        .zip(&pre_accounts)
+        .zip(&simulation.accounts)
+        .skip(1)
+        .filter_map(|((address, pre_account), post_account)| {
+            let pre = pre_account.as_ref().and_then(token_account);
+            let post = post_account.as_ref().and_then(token_account);
+            let (mint, _, _) = [pre, post]
+                .into_iter()
+                .flatten()
+                .find(|(_, owner, _)| owner == user_public_key)?;
+            let amount =
+                |balance: Option<(Pubkey, Pubkey, u64)>| balance.map_or(0, |(_, _, amount)| amount);
+            Some(TokenBalanceDelta {
+                account: *address,
+                mint,
+                pre_amount: amount(pre),
+                post_amount: amount(post),
+            })
+        })
+        .collect();
+
+    Ok(SimulationReport {
+        program_error: simulation
+            .err
+            .as_ref()
+            .and_then(|err| program_error(&transaction.message, err)),
+        err: simulation.err,
+        logs: simulation.logs,
+        units_consumed: simulation.units_consumed,
+        lamport_change,
+        token_balance_deltas,
+    })
+}
+
+/// Rewrite the `SetComputeUnitLimit` instruction of `transaction` to `units`.
+/// The signatures are cleared since the message changes, sign the transaction afterwards
+pub fn set_compute_unit_limit(transaction: &mut VersionedTransaction, units: u32) -> Result<()> {
+    let compute_budget_index = transaction
+        .message
+        .static_account_keys()
+        .iter()
+        .position(|key| key == &compute_budget::id());
+    let instructions = match &mut transaction.message {
+        VersionedMessage::Legacy(message) => &mut message.instructions,
+        VersionedMessage::V0(message) => &mut message.instructions,
+    };
+    let instruction = instructions
+        .iter_mut()
+        .find(|instruction| {
+            Some(instruction.program_id_index as usize) == compute_budget_index
+                && instruction.data.first() == Some(&SET_COMPUTE_UNIT_LIMIT_TAG)
+        })
+        .ok_or(ExecutionError::MissingComputeUnitLimit)?;
+    instruction.data = ComputeBudgetInstruction::set_compute_unit_limit(units).data;
+    transaction
+        .signatures
+        .iter_mut()
+        .for_each(|signature| *signature = Signature::default());
+    Ok(())
+}
+
+/// Mint, owner and amount of a token account
+fn token_account(account: &Account) -> Option<(Pubkey, Pubkey, u64)> {
+    let data = &account.data;
+    let is_token_account = account.owner == TOKEN_PROGRAM_ID && data.len() == TOKEN_ACCOUNT_SIZE
+        || account.owner == TOKEN_2022_PROGRAM_ID
+            && (data.len() == TOKEN_ACCOUNT_SIZE
+                || data.get(TOKEN_ACCOUNT_SIZE) == Some(&TOKEN_ACCOUNT_TYPE));
+    if !is_token_account {
+        return None;
+    }
+    Some((
+        Pubkey::new_from_array(data[..32].try_into().ok()?),
+        Pubkey::new_from_array(data[32..64].try_into().ok()?),
+        u64::from_le_bytes(data[64..72].try_into().ok()?),
+    ))
+}
+
+/// Writable accounts of `message`, including those loaded from address lookup tables
+async fn writable_accounts<R: SolanaRpc + ?Sized>(
+    rpc: &R,
+    message: &VersionedMessage,
+) -> Result<Vec<Pubkey>> {
+    let mut writable_accounts = message
+        .static_account_keys()
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| message.is_maybe_writable(*index))
+        .map(|(_, key)| *key)
+        .collect::<Vec<_>>();
+    if let Some(address_table_lookups) = message.address_table_lookups() {
+        let table_addresses = address_table_lookups
+            .iter()
+            .map(|table_lookup| table_lookup.account_key)
+            .collect::<Vec<_>>();
+        let tables = rpc
+            .get_address_lookup_tables(&table_addresses)
+            .await?
+            .into_iter()
+            .map(|table| (table.key, table.addresses))
+            .collect::<HashMap<_, _>>();
+        for table_lookup in address_table_lookups {
+            let addresses = tables.get(&table_lookup.account_key).ok_or(
+                SwapTransactionError::MissingAddressLookupTable(table_lookup.account_key),
+            )?;
+            writable_accounts.extend(
+                table_lookup
+                    .writable_indexes
+                    .iter()
+                    .filter_map(|index| addresses.get(*index as usize)),
+            );
+        }
+    }
+    Ok(writable_accounts)
+}
+
+fn program_error(message: &VersionedMessage, err: &TransactionError) -> Option<ProgramError> {
+    let TransactionError::InstructionError(instruction_index, InstructionError::Custom(code)) = err
+    else {
+        return None;
+    };
+    let program_id = message
+        .instructions()
+        .get(*instruction_index as usize)
+        .and_then(|instruction| {
+            message
+                .static_account_keys()
+                .get(instruction.program_id_index as usize)
+                .copied()
+        });
+    Some(ProgramError {
+        instruction_index: *instruction_index,
+        program_id,
+        code: *code,
+        jupiter_error: match program_id {
+            Some(JUPITER_PROGRAM_ID) => JupiterProgramError::from_code(*code),
+            _ => None,
+        },
+    })
+}
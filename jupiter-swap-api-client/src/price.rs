@@ -0,0 +1,137 @@
+//! Price API data structures, prices are in USDC unless a vs token is given
+//!
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize, Serializer};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::serde_helpers::{field_as_string, map_keys_as_string, option_field_as_string};
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceRequest {
+    #[serde(serialize_with = "serialize_ids")]
+    pub ids: Vec<Pubkey>,
+    /// Price the ids in this token instead of USDC, cannot be combined with `show_extra_info`
+    #[serde(
+        with = "option_field_as_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub vs_token: Option<Pubkey>,
+    /// Include the confidence level, depth and last swapped prices
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub show_extra_info: bool,
+}
+
+impl PriceRequest {
+    pub fn new(ids: Vec<Pubkey>) -> Self {
+        Self {
+            ids,
+            ..Self::default()
+        }
+    }
+}
+
+fn serialize_ids<S: Serializer>(ids: &[Pubkey], serializer: S) -> Result<S::Ok, S::Error> {
+    ids.iter()
+        .map(Pubkey::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+        .serialize(serializer)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceResponse {
+    /// `None` for tokens without a reliable price
+    #[serde(with = "map_keys_as_string")]
+    pub data: HashMap<Pubkey, Option<TokenPrice>>,
+    #[serde(default)]
+    pub time_taken: f64,
+}
+
+impl PriceResponse {
+    pub fn get(&self, mint: &Pubkey) -> Option<&TokenPrice> {
+        self.data.get(mint).and_then(Option::as_ref)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum PriceType {
+    /// Midpoint between the buy and sell prices
+    DerivedPrice,
+    /// Price of buying the token, used with a vs token
+    BuyPrice,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenPrice {
+    #[serde(with = "field_as_string")]
+    pub id: Pubkey,
+    #[serde(rename = "type")]
+    pub price_type: PriceType,
+    #[serde(with = "field_as_string")]
+    pub price: f64,
+    pub extra_info: Option<PriceExtraInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfidenceLevel {
+    High,
+    Medium,
+    Low,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceExtraInfo {
+    pub last_swapped_price: Option<LastSwappedPrice>,
+    pub quoted_price: Option<QuotedPrice>,
+    pub confidence_level: Option<ConfidenceLevel>,
+    pub depth: Option<PriceDepth>,
+}
+
+/// Prices of the last swaps through Jupiter, timestamps are unix seconds
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LastSwappedPrice {
+    pub last_jupiter_sell_at: Option<i64>,
+    #[serde(default, with = "option_field_as_string")]
+    pub last_jupiter_sell_price: Option<f64>,
+    pub last_jupiter_buy_at: Option<i64>,
+    #[serde(default, with = "option_field_as_string")]
+    pub last_jupiter_buy_price: Option<f64>,
+}
+
+/// Prices quoted for buying and selling the token, timestamps are unix seconds
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotedPrice {
+    #[serde(default, with = "option_field_as_string")]
+    pub buy_price: Option<f64>,
+    pub buy_at: Option<i64>,
+    #[serde(default, with = "option_field_as_string")]
+    pub sell_price: Option<f64>,
+    pub sell_at: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceDepth {
+    pub buy_price_impact_ratio: Option<PriceImpactRatio>,
+    pub sell_price_impact_ratio: Option<PriceImpactRatio>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceImpactRatio {
+    /// Price impact ratio by trade size in USD, e.g. 10, 100 and 1000
+    pub depth: BTreeMap<u64, f64>,
+    pub timestamp: i64,
+}
@@ -1,11 +1,14 @@
 //! Quote data structure for quoting and quote response
 //!
 
-use std::str::FromStr;
+use std::{collections::HashMap, str::FromStr, time::Instant};
 
+use crate::amount::{amount_after_slippage, amount_with_slippage, effective_price};
+use crate::error::JupiterError;
+use crate::route_graph::RouteGraph;
 use crate::route_plan_with_metadata::RoutePlanWithMetadata;
 use crate::serde_helpers::field_as_string;
-use anyhow::{anyhow, Error};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 
@@ -40,13 +43,13 @@ pub enum SwapMode {
 }
 
 impl FromStr for SwapMode {
-    type Err = Error;
+    type Err = JupiterError;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         match s {
             "ExactIn" => Ok(Self::ExactIn),
             "ExactOut" => Ok(Self::ExactOut),
-            _ => Err(anyhow!("{} is not a valid SwapMode", s)),
+            _ => Err(JupiterError::InvalidSwapMode(s.to_string())),
         }
     }
 }
@@ -110,4 +113,51 @@ pub struct QuoteResponse {
     pub context_slot: u64,
     #[serde(default)]
     pub time_taken: f64,
+    #[serde(skip)]
+    pub(crate) received_at: Option<Instant>,
+}
+
+impl QuoteResponse {
+    /// When the quote was received by `quote`, `None` for a quote deserialized or built by the caller
+    pub fn received_at(&self) -> Option<Instant> {
+        self.received_at
+    }
+
+    /// `price_impact_pct` parsed without going through `f64`
+    pub fn price_impact(&self) -> std::result::Result<Decimal, rust_decimal::Error> {
+        Decimal::from_str_exact(&self.price_impact_pct)
+            .or_else(|_| Decimal::from_scientific(&self.price_impact_pct))
+    }
+
+    /// Output per input in human readable units
+    pub fn effective_price(&self, input_decimals: u8, output_decimals: u8) -> Option<Decimal> {
+        effective_price(
+            self.in_amount,
+            input_decimals,
+            self.out_amount,
+            output_decimals,
+        )
+    }
+
+    /// Output amount received at worst after `slippage_bps`
+    pub fn minimum_received(&self) -> Option<u64> {
+        match self.swap_mode {
+            SwapMode::ExactIn => amount_after_slippage(self.out_amount, self.slippage_bps),
+            SwapMode::ExactOut => Some(self.out_amount),
+        }
+    }
+
+    /// Input amount sent at worst after `slippage_bps`
+    pub fn maximum_sent(&self) -> Option<u64> {
+        match self.swap_mode {
+            SwapMode::ExactIn => Some(self.in_amount),
+            SwapMode::ExactOut => amount_with_slippage(self.in_amount, self.slippage_bps),
+        }
+    }
+
+    /// Fees of every step of `route_plan` summed by fee mint, `None` on overflow.
+    /// The platform fee is not included
+    pub fn route_fees(&self) -> Option<HashMap<Pubkey, u64>> {
+        RouteGraph::from_quote(self).fees_by_mint()
+    }
 }
@@ -0,0 +1,162 @@
+//! Client side token bucket rate limiting, one bucket per endpoint
+//!
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::time::Instant;
+
+use crate::{endpoint::Endpoint, error::JupiterError, Result};
+
+/// Only built by [`RateLimit::new`], so that every limit is valid
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    requests_per_second: f64,
+    burst: u32,
+}
+
+impl RateLimit {
+    /// `requests_per_second` is the rate at which permits are refilled, it must be positive and finite.
+    /// `burst` is the number of permits available at once after a quiet period, it must be at least 1
+    pub fn new(requests_per_second: f64, burst: u32) -> Result<Self> {
+        if !(requests_per_second.is_finite() && requests_per_second > 0.0) {
+            return Err(JupiterError::InvalidRateLimit(
+                "requests_per_second must be positive and finite",
+            ));
+        }
+        if burst == 0 {
+            return Err(JupiterError::InvalidRateLimit("burst must be at least 1"));
+        }
+        Ok(Self {
+            requests_per_second,
+            burst,
+        })
+    }
+
+    pub fn requests_per_second(&self) -> f64 {
+        self.requests_per_second
+    }
+
+    pub fn burst(&self) -> u32 {
+        self.burst
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RateLimitMode {
+    /// Wait until a permit is available
+    #[default]
+    Wait,
+    /// Return [`JupiterError::RateLimited`] when no permit is available
+    FailFast,
+}
+
+/// Cheap to clone, clones share the same buckets so one limiter can be given to several clients using the same API key
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    mode: RateLimitMode,
+    buckets: HashMap<Endpoint, Arc<Mutex<TokenBucket>>>,
+}
+
+impl RateLimiter {
+    /// Limiter without any limit, see [`RateLimiter::limit`]
+    pub fn new(mode: RateLimitMode) -> Self {
+        Self {
+            mode,
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Same limit for every endpoint, each endpoint still has its own bucket
+    pub fn per_endpoint(rate_limit: RateLimit, mode: RateLimitMode) -> Self {
+        Endpoint::ALL
+            .iter()
+            .copied()
+            .fold(Self::new(mode), |limiter, endpoint| {
+                limiter.limit(endpoint, rate_limit)
+            })
+    }
+
+    /// Limit `endpoint`, endpoints without a limit are not throttled
+    pub fn limit(mut self, endpoint: Endpoint, rate_limit: RateLimit) -> Self {
+        self.buckets
+            .insert(endpoint, Arc::new(Mutex::new(TokenBucket::new(rate_limit))));
+        self
+    }
+
+    /// Take a permit for `endpoint`, waiting for it or failing depending on the mode
+    pub async fn acquire(&self, endpoint: Endpoint) -> Result<()> {
+        let Some(bucket) = self.buckets.get(&endpoint) else {
+            return Ok(());
+        };
+        let wait = {
+            let mut bucket = bucket.lock().unwrap();
+            match self.mode {
+                RateLimitMode::Wait => bucket.reserve(Instant::now()),
+                RateLimitMode::FailFast => {
+                    if !bucket.try_take(Instant::now()) {
+                        return Err(JupiterError::RateLimited(endpoint));
+                    }
+                    Duration::ZERO
+                }
+            }
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    rate_limit: RateLimit,
+    /// Negative when permits have been reserved ahead of time by waiting callers
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate_limit: RateLimit) -> Self {
+        Self {
+            rate_limit,
+            tokens: rate_limit.burst as f64,
+            refilled_at: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.refilled_at)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate_limit.requests_per_second)
+            .min(self.rate_limit.burst as f64);
+        self.refilled_at = now;
+    }
+
+    fn try_take(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Take a permit, possibly in advance, and return how long to wait before using it
+    fn reserve(&mut self, now: Instant) -> Duration {
+        self.refill(now);
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            // A tiny rate can put the permit beyond any representable delay
+            Duration::try_from_secs_f64(-self.tokens / self.rate_limit.requests_per_second)
+                .unwrap_or(Duration::MAX)
+        }
+    }
+}
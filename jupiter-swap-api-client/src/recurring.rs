@@ -0,0 +1,323 @@
+//! Recurring order (DCA) API data structures, an order swaps a fixed input amount every interval
+//!
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::{
+    serde_helpers::{field_as_string, option_field_as_string},
+    transaction::EncodedTransaction,
+    ultra::ExecuteStatus,
+};
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRecurringOrderRequest {
+    #[serde(with = "field_as_string")]
+    pub user: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub input_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub output_mint: Pubkey,
+    pub params: RecurringOrderParams,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum RecurringOrderParams {
+    Time(TimeRecurringParams),
+    Price(PriceRecurringParams),
+}
+
+impl Default for RecurringOrderParams {
+    fn default() -> Self {
+        Self::Time(TimeRecurringParams::default())
+    }
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeRecurringParams {
+    /// Total input amount deposited, in base units, split evenly across the orders
+    pub in_amount: u64,
+    pub number_of_orders: u64,
+    /// Seconds between two orders
+    pub interval: u64,
+    /// Skip a cycle when the price of the output token in input tokens is below this bound
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub min_price: Option<Decimal>,
+    /// Skip a cycle when the price of the output token in input tokens is above this bound
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub max_price: Option<Decimal>,
+    /// Unix timestamp in seconds of the first order, immediately when `None`
+    pub start_at: Option<i64>,
+}
+
+impl TimeRecurringParams {
+    /// `number_of_orders` orders of `in_amount_per_cycle` every `interval` seconds,
+    /// `None` when the total input amount overflows
+    pub fn per_cycle(
+        in_amount_per_cycle: u64,
+        number_of_orders: u64,
+        interval: u64,
+    ) -> Option<Self> {
+        Some(Self {
+            in_amount: in_amount_per_cycle.checked_mul(number_of_orders)?,
+            number_of_orders,
+            interval,
+            ..Self::default()
+        })
+    }
+}
+
+/// Value averaging: every interval, buys until the position has grown by `increment_usdc_value`
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceRecurringParams {
+    /// Input amount deposited, in base units, more can be added with a deposit
+    pub deposit_amount: u64,
+    /// USDC value the position grows by every interval
+    pub increment_usdc_value: u64,
+    /// Seconds between two orders
+    pub interval: u64,
+    /// Unix timestamp in seconds of the first order, immediately when `None`
+    pub start_at: Option<i64>,
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelRecurringOrderRequest {
+    #[serde(with = "field_as_string")]
+    pub order: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub user: Pubkey,
+    pub recurring_type: RecurringType,
+}
+
+/// Add input tokens to a price based order
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositRecurringOrderRequest {
+    #[serde(with = "field_as_string")]
+    pub order: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub user: Pubkey,
+    /// Input amount added to the order, in base units
+    pub amount: u64,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum WithdrawSide {
+    /// Unused input tokens
+    #[default]
+    In,
+    /// Received output tokens
+    Out,
+}
+
+/// Withdraw from a price based order
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawRecurringOrderRequest {
+    #[serde(with = "field_as_string")]
+    pub order: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub user: Pubkey,
+    pub input_or_output: WithdrawSide,
+    /// Amount withdrawn in base units, everything when `None`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<u64>,
+}
+
+/// Unsigned transaction to be signed by the user
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringTransactionResponse {
+    /// Sent back with the signed transaction to `recurring_execute`
+    pub request_id: String,
+    pub transaction: EncodedTransaction,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringExecuteResponse {
+    pub status: ExecuteStatus,
+    /// Present once the transaction was sent, even if it failed
+    #[serde(default, with = "option_field_as_string")]
+    pub signature: Option<Signature>,
+    /// Order created by the transaction
+    #[serde(default, with = "option_field_as_string")]
+    pub order: Option<Pubkey>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RecurringType {
+    #[default]
+    Time,
+    Price,
+    All,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RecurringOrderStatus {
+    #[default]
+    Active,
+    History,
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringOrdersRequest {
+    #[serde(with = "field_as_string")]
+    pub user: Pubkey,
+    pub order_status: RecurringOrderStatus,
+    pub recurring_type: RecurringType,
+    pub include_failed_tx: bool,
+    /// Page number, starting at 1
+    pub page: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringOrdersResponse {
+    #[serde(with = "field_as_string")]
+    pub user: Pubkey,
+    pub order_status: RecurringOrderStatus,
+    #[serde(default)]
+    pub time: Vec<TimeRecurringOrder>,
+    #[serde(default)]
+    pub price: Vec<PriceRecurringOrder>,
+    pub page: u32,
+    pub total_pages: u32,
+}
+
+/// Time based recurring order, `raw_` amounts are in base units and the others in human readable units
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeRecurringOrder {
+    #[serde(with = "field_as_string")]
+    pub user_pubkey: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub order_key: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub input_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub output_mint: Pubkey,
+    /// Seconds between two orders
+    #[serde(with = "field_as_string")]
+    pub cycle_frequency: u64,
+    pub in_deposited: String,
+    pub in_withdrawn: String,
+    pub in_used: String,
+    pub in_amount_per_cycle: String,
+    pub out_received: String,
+    pub out_withdrawn: String,
+    #[serde(with = "field_as_string")]
+    pub raw_in_deposited: u64,
+    #[serde(with = "field_as_string")]
+    pub raw_in_withdrawn: u64,
+    #[serde(with = "field_as_string")]
+    pub raw_in_used: u64,
+    #[serde(with = "field_as_string")]
+    pub raw_in_amount_per_cycle: u64,
+    #[serde(with = "field_as_string")]
+    pub raw_out_received: u64,
+    #[serde(with = "field_as_string")]
+    pub raw_out_withdrawn: u64,
+    /// Minimum output amount per cycle derived from `max_price`
+    #[serde(default, with = "option_field_as_string")]
+    pub raw_min_out_amount: Option<u64>,
+    /// Maximum output amount per cycle derived from `min_price`
+    #[serde(default, with = "option_field_as_string")]
+    pub raw_max_out_amount: Option<u64>,
+    #[serde(with = "field_as_string")]
+    pub open_tx: Signature,
+    #[serde(default, with = "option_field_as_string")]
+    pub close_tx: Option<Signature>,
+    #[serde(default)]
+    pub user_closed: bool,
+    pub created_at: String,
+    pub updated_at: String,
+    /// Fills of the order, one per executed cycle
+    #[serde(default)]
+    pub trades: Vec<RecurringTrade>,
+}
+
+impl TimeRecurringOrder {
+    /// Input amount left to be swapped, in base units
+    pub fn raw_in_remaining(&self) -> u64 {
+        self.raw_in_deposited
+            .saturating_sub(self.raw_in_withdrawn)
+            .saturating_sub(self.raw_in_used)
+    }
+}
+
+/// Price based recurring order, `raw_` amounts are in base units and the others in human readable units
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceRecurringOrder {
+    #[serde(with = "field_as_string")]
+    pub user_pubkey: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub order_key: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub input_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub output_mint: Pubkey,
+    /// Seconds between two orders
+    #[serde(with = "field_as_string")]
+    pub order_interval: u64,
+    pub incremental_usd_value: String,
+    pub in_deposited: String,
+    pub in_withdrawn: String,
+    pub in_left: String,
+    pub in_used: String,
+    pub out_received: String,
+    pub out_withdrawn: String,
+    #[serde(with = "field_as_string")]
+    pub raw_in_deposited: u64,
+    #[serde(with = "field_as_string")]
+    pub raw_in_withdrawn: u64,
+    /// Input amount left to be swapped
+    #[serde(with = "field_as_string")]
+    pub raw_in_left: u64,
+    #[serde(with = "field_as_string")]
+    pub raw_in_used: u64,
+    #[serde(with = "field_as_string")]
+    pub raw_out_received: u64,
+    #[serde(with = "field_as_string")]
+    pub raw_out_withdrawn: u64,
+    #[serde(with = "field_as_string")]
+    pub open_tx: Signature,
+    #[serde(default, with = "option_field_as_string")]
+    pub close_tx: Option<Signature>,
+    pub created_at: String,
+    pub updated_at: String,
+    /// Fills of the order, one per executed cycle
+    #[serde(default)]
+    pub trades: Vec<RecurringTrade>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringTrade {
+    #[serde(with = "field_as_string")]
+    pub keeper: Pubkey,
+    pub input_amount: String,
+    pub output_amount: String,
+    #[serde(with = "field_as_string")]
+    pub raw_input_amount: u64,
+    #[serde(with = "field_as_string")]
+    pub raw_output_amount: u64,
+    #[serde(with = "field_as_string")]
+    pub fee_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub raw_fee_amount: u64,
+    #[serde(with = "field_as_string")]
+    pub tx_id: Signature,
+    pub confirmed_at: String,
+}
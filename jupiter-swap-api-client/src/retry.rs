@@ -0,0 +1,90 @@
+//! Retry with exponential backoff for transient failures
+//!
+
+use std::{future::Future, time::Duration};
+
+use rand::Rng;
+
+use crate::{error::JupiterError, Result};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt, 0 disables retrying
+    pub max_retries: u32,
+    /// Backoff before the first retry, doubled on each following retry
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Cap on the delay requested by a server `Retry-After`
+    pub max_retry_after: Duration,
+    /// Randomize each backoff between half and the full value, so that clients failing together do not retry together
+    pub jitter: bool,
+    /// Also retry the endpoints building an unsigned transaction: `swap`, `swap_instructions`,
+    /// `create_limit_order`, `cancel_limit_orders`, `create_recurring_order`, `cancel_recurring_order`,
+    /// `deposit_recurring_order` and `withdraw_recurring_order`.
+    /// A retried call may return a different transaction for the same request.
+    /// `ultra_execute` and `recurring_execute`, which submit a signed transaction, are never retried
+    ///
+    /// Default: false
+    pub retry_swap: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(2),
+            max_retry_after: Duration::from_secs(10),
+            jitter: true,
+            retry_swap: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// See [`JupiterError::is_transient`]
+    pub fn is_transient(&self, error: &JupiterError) -> bool {
+        error.is_transient()
+    }
+
+    /// Delay before retry number `retry` (0-based), `Retry-After` capped at `max_retry_after` takes
+    /// precedence over the backoff
+    pub fn delay(&self, retry: u32, error: &JupiterError) -> Duration {
+        if let Some(retry_after) = error.retry_after() {
+            return retry_after.min(self.max_retry_after);
+        }
+        let backoff = self
+            .initial_backoff
+            .checked_mul(2u32.saturating_pow(retry))
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff));
+        if self.jitter && !backoff.is_zero() {
+            rand::thread_rng().gen_range(backoff / 2..=backoff)
+        } else {
+            backoff
+        }
+    }
+}
+
+/// Run `send` until it succeeds, fails with a non transient error or the policy gives up
+pub(crate) async fn retry<T, F, Fut>(
+    policy: Option<&RetryPolicy>,
+    retryable: bool,
+    send: F,
+) -> Result<T>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut retries = 0;
+    loop {
+        match (send().await, policy) {
+            (Err(error), Some(policy))
+                if retryable && retries < policy.max_retries && policy.is_transient(&error) =>
+            {
+                tokio::time::sleep(policy.delay(retries, &error)).await;
+                retries += 1;
+            }
+            (result, _) => return result,
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Render a route as Graphviz DOT, a Mermaid flowchart or an ASCII tree
+//!
+
+use std::{collections::HashMap, fmt::Write};
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{route_graph::RouteGraph, route_plan_with_metadata::RoutePlanStep, token::TokenInfo};
+
+/// Mints are shown by symbol when known, otherwise by their shortened address
+#[derive(Clone, Debug)]
+pub struct RouteExporter<'a> {
+    route_graph: RouteGraph<'a>,
+    symbols: HashMap<Pubkey, String>,
+}
+
+impl<'a> RouteExporter<'a> {
+    pub fn new(route_graph: RouteGraph<'a>) -> Self {
+        Self {
+            route_graph,
+            symbols: HashMap::new(),
+        }
+    }
+
+    pub fn symbol(mut self, mint: Pubkey, symbol: String) -> Self {
+        self.symbols.insert(mint, symbol);
+        self
+    }
+
+    /// Symbols of the tokens returned by the Token API
+    pub fn tokens<'t>(mut self, tokens: impl IntoIterator<Item = &'t TokenInfo>) -> Self {
+        self.symbols.extend(
+            tokens
+                .into_iter()
+                .map(|token| (token.address, token.symbol.clone())),
+        );
+        self
+    }
+
+    fn mint_label(&self, mint: &Pubkey) -> String {
+        match self.symbols.get(mint) {
+            Some(symbol) => symbol.clone(),
+            None => {
+                let mint = mint.to_string();
+                format!("{}…{}", &mint[..4], &mint[mint.len() - 4..])
+            }
+        }
+    }
+
+    fn step_lines(&self, step: &RoutePlanStep) -> [String; 3] {
+        let swap_info = &step.swap_info;
+        [
+            format!("{} {}%", swap_info.label, step.percent),
+            format!("in {}, out {}", swap_info.in_amount, swap_info.out_amount),
+            format!(
+                "fee {} {}",
+                swap_info.fee_amount,
+                self.mint_label(&swap_info.fee_mint)
+            ),
+        ]
+    }
+
+    fn node_ids(&self) -> Vec<(Pubkey, String)> {
+        self.route_graph
+            .mints()
+            .into_iter()
+            .enumerate()
+            .map(|(index, mint)| (mint, format!("n{index}")))
+            .collect()
+    }
+
+    /// Graphviz DOT digraph, render with `dot -Tsvg`
+    pub fn dot(&self) -> String {
+        let node_ids = self.node_ids();
+        let node_id = |mint: &Pubkey| lookup(&node_ids, mint);
+        let mut dot = String::from("digraph route {\n    rankdir=LR;\n");
+        for (mint, id) in &node_ids {
+            let _ = writeln!(
+                dot,
+                "    {id} [label=\"{}\"];",
+                escape_dot(&self.mint_label(mint))
+            );
+        }
+        for step in self.route_graph.steps() {
+            let label = self
+                .step_lines(step)
+                .map(|line| escape_dot(&line))
+                .join("\\n");
+            let _ = writeln!(
+                dot,
+                "    {} -> {} [label=\"{label}\"];",
+                node_id(&step.swap_info.input_mint),
+                node_id(&step.swap_info.output_mint),
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Mermaid flowchart, renders in Markdown code blocks tagged `mermaid`
+    pub fn mermaid(&self) -> String {
+        let node_ids = self.node_ids();
+        let node_id = |mint: &Pubkey| lookup(&node_ids, mint);
+        let mut mermaid = String::from("flowchart LR\n");
+        for (mint, id) in &node_ids {
+            let _ = writeln!(
+                mermaid,
+                "    {id}[\"{}\"]",
+                escape_mermaid(&self.mint_label(mint))
+            );
+        }
+        for step in self.route_graph.steps() {
+            let label = self
+                .step_lines(step)
+                .map(|line| escape_mermaid(&line))
+                .join("<br/>");
+            let _ = writeln!(
+                mermaid,
+                "    {} -->|\"{label}\"| {}",
+                node_id(&step.swap_info.input_mint),
+                node_id(&step.swap_info.output_mint),
+            );
+        }
+        mermaid
+    }
+
+    /// Tree of the steps from the input mint, for terminals and logs
+    pub fn ascii_tree(&self) -> String {
+        let input_mint = self.route_graph.input_mint();
+        let mut tree = self.mint_label(&input_mint);
+        tree.push('\n');
+        self.write_subtree(&mut tree, &input_mint, "", self.route_graph.steps().len());
+        tree
+    }
+
+    fn write_subtree(&self, tree: &mut String, mint: &Pubkey, prefix: &str, depth: usize) {
+        // The route is a DAG, the depth only bounds malformed route plans
+        if depth == 0 {
+            return;
+        }
+        let outgoing = self.route_graph.outgoing(mint).collect::<Vec<_>>();
+        for (index, step) in outgoing.iter().enumerate() {
+            let last = index + 1 == outgoing.len();
+            let [amm, amounts, fee] = self.step_lines(step);
+            let _ = writeln!(
+                tree,
+                "{prefix}{} {amm} → {} ({amounts}, {fee})",
+                if last { "└──" } else { "├──" },
+                self.mint_label(&step.swap_info.output_mint),
+            );
+            let child_prefix = format!("{prefix}{}", if last { "    " } else { "│   " });
+            self.write_subtree(tree, &step.swap_info.output_mint, &child_prefix, depth - 1);
+        }
+    }
+}
+
+fn lookup<'n>(node_ids: &'n [(Pubkey, String)], mint: &Pubkey) -> &'n str {
+    node_ids
+        .iter()
+        .find(|(node_mint, _)| node_mint == mint)
+        .map(|(_, id)| id.as_str())
+        .unwrap_or_default()
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
@@ -0,0 +1,181 @@
+//! DAG view of a route plan, for approving routes before executing them
+//!
+
+use std::collections::{HashMap, HashSet};
+
+use solana_sdk::pubkey::Pubkey;
+use thiserror::Error;
+
+use crate::{
+    quote::QuoteResponse,
+    route_plan_with_metadata::{RoutePlanStep, RoutePlanWithMetadata},
+    Result,
+};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum RouteError {
+    /// The splits of the steps leaving a mint of the route plan do not add up to 100
+    #[error("route splits from {mint} add up to {total_percent}%")]
+    InvalidSplit { mint: Pubkey, total_percent: u32 },
+    /// The route passes through a mint outside the allowlist
+    #[error("route passes through mint {0} outside the allowlist")]
+    MintNotAllowed(Pubkey),
+}
+
+/// Mints are the nodes and the steps of the route plan the edges, in topological order
+#[derive(Clone, Copy, Debug)]
+pub struct RouteGraph<'a> {
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    steps: &'a [RoutePlanStep],
+}
+
+impl<'a> RouteGraph<'a> {
+    pub fn new(
+        input_mint: Pubkey,
+        output_mint: Pubkey,
+        route_plan: &'a RoutePlanWithMetadata,
+    ) -> Self {
+        Self {
+            input_mint,
+            output_mint,
+            steps: route_plan,
+        }
+    }
+
+    pub fn from_quote(quote_response: &'a QuoteResponse) -> Self {
+        Self::new(
+            quote_response.input_mint,
+            quote_response.output_mint,
+            &quote_response.route_plan,
+        )
+    }
+
+    pub fn input_mint(&self) -> Pubkey {
+        self.input_mint
+    }
+
+    pub fn output_mint(&self) -> Pubkey {
+        self.output_mint
+    }
+
+    pub fn steps(&self) -> &'a [RoutePlanStep] {
+        self.steps
+    }
+
+    /// Every mint of the route in topological order, starting with the input mint
+    pub fn mints(&self) -> Vec<Pubkey> {
+        let mut seen = HashSet::new();
+        [self.input_mint]
+            .into_iter()
+            .chain(
+                self.steps
+                    .iter()
+                    .flat_map(|step| [step.swap_info.input_mint, step.swap_info.output_mint]),
+            )
+            .chain([self.output_mint])
+            .filter(|mint| seen.insert(*mint))
+            .collect()
+    }
+
+    /// Mints the route passes through, other than the input and output mints
+    pub fn intermediate_mints(&self) -> Vec<Pubkey> {
+        self.mints()
+            .into_iter()
+            .filter(|mint| *mint != self.input_mint && *mint != self.output_mint)
+            .collect()
+    }
+
+    /// Steps swapping from `mint`
+    pub fn outgoing(&self, mint: &Pubkey) -> impl Iterator<Item = &'a RoutePlanStep> + '_ {
+        let mint = *mint;
+        self.steps
+            .iter()
+            .filter(move |step| step.swap_info.input_mint == mint)
+    }
+
+    /// Steps swapping to `mint`
+    pub fn incoming(&self, mint: &Pubkey) -> impl Iterator<Item = &'a RoutePlanStep> + '_ {
+        let mint = *mint;
+        self.steps
+            .iter()
+            .filter(move |step| step.swap_info.output_mint == mint)
+    }
+
+    /// Check that the `percent` of the steps leaving each mint add up to 100
+    pub fn validate_splits(&self) -> Result<()> {
+        let mut totals: Vec<(Pubkey, u32)> = Vec::new();
+        for step in self.steps {
+            let input_mint = step.swap_info.input_mint;
+            match totals.iter_mut().find(|(mint, _)| *mint == input_mint) {
+                Some((_, total)) => *total += step.percent as u32,
+                None => totals.push((input_mint, step.percent as u32)),
+            }
+        }
+        match totals.into_iter().find(|(_, total)| *total != 100) {
+            Some((mint, total_percent)) => Err(RouteError::InvalidSplit {
+                mint,
+                total_percent,
+            }
+            .into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Steps on the longest path from the input mint, 1 for a direct route
+    pub fn hop_count(&self) -> usize {
+        let mut depths = HashMap::from([(self.input_mint, 0)]);
+        for step in self.steps {
+            let depth = depths
+                .get(&step.swap_info.input_mint)
+                .copied()
+                .unwrap_or_default()
+                + 1;
+            let output_depth = depths.entry(step.swap_info.output_mint).or_default();
+            *output_depth = (*output_depth).max(depth);
+        }
+        depths.values().copied().max().unwrap_or_default()
+    }
+
+    /// Pools the route swaps through
+    pub fn amm_keys(&self) -> HashSet<Pubkey> {
+        self.steps
+            .iter()
+            .map(|step| step.swap_info.amm_key)
+            .collect()
+    }
+
+    /// DEX labels the route swaps through
+    pub fn labels(&self) -> HashSet<&'a str> {
+        self.steps
+            .iter()
+            .map(|step| step.swap_info.label.as_str())
+            .collect()
+    }
+
+    /// Fees of every step summed by fee mint, `None` on overflow
+    pub fn fees_by_mint(&self) -> Option<HashMap<Pubkey, u64>> {
+        let mut fees = HashMap::new();
+        for step in self.steps {
+            let fee: &mut u64 = fees.entry(step.swap_info.fee_mint).or_default();
+            *fee = fee.checked_add(step.swap_info.fee_amount)?;
+        }
+        Some(fees)
+    }
+
+    /// Intermediate mints missing from `allowlist`
+    pub fn disallowed_mints(&self, allowlist: &HashSet<Pubkey>) -> Vec<Pubkey> {
+        self.intermediate_mints()
+            .into_iter()
+            .filter(|mint| !allowlist.contains(mint))
+            .collect()
+    }
+
+    /// Fail on the first intermediate mint missing from `allowlist`
+    pub fn check_allowlist(&self, allowlist: &HashSet<Pubkey>) -> Result<()> {
+        match self.disallowed_mints(allowlist).first() {
+            Some(mint) => Err(RouteError::MintNotAllowed(*mint).into()),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+};
+
+use async_trait::async_trait;
+use solana_sdk::{
+    account::Account, address_lookup_table_account::AddressLookupTableAccount, hash::Hash,
+    pubkey::Pubkey, signature::Signature, transaction::VersionedTransaction,
+};
+
+use super::{ConfirmedTransaction, RpcError, SignatureStatus, SimulationResult, SolanaRpc};
+use crate::Result;
+
+/// In-memory [`SolanaRpc`] for offline tests: serves configured state and records what is sent
+#[derive(Debug, Default)]
+pub struct InMemorySolanaRpc {
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    address_lookup_tables: HashMap<Pubkey, AddressLookupTableAccount>,
+    latest_blockhash: (Hash, u64),
+    accounts: HashMap<Pubkey, Account>,
+    /// State of accounts after the next simulations, unchanged accounts are read from `accounts`
+    simulated_accounts: HashMap<Pubkey, Account>,
+    /// Served in order, the default result is returned once empty
+    simulation_results: VecDeque<SimulationResult>,
+    simulated_transactions: Vec<VersionedTransaction>,
+    sent_transactions: Vec<VersionedTransaction>,
+    block_height: u64,
+    signature_statuses: HashMap<Signature, SignatureStatus>,
+    transactions: HashMap<Signature, ConfirmedTransaction>,
+}
+
+impl InMemorySolanaRpc {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_address_lookup_table(&self, address_lookup_table: AddressLookupTableAccount) {
+        self.state
+            .lock()
+            .unwrap()
+            .address_lookup_tables
+            .insert(address_lookup_table.key, address_lookup_table);
+    }
+
+    pub fn set_latest_blockhash(&self, blockhash: Hash, last_valid_block_height: u64) {
+        self.state.lock().unwrap().latest_blockhash = (blockhash, last_valid_block_height);
+    }
+
+    pub fn set_account(&self, address: Pubkey, account: Account) {
+        self.state.lock().unwrap().accounts.insert(address, account);
+    }
+
+    pub fn set_simulated_account(&self, address: Pubkey, account: Account) {
+        self.state
+            .lock()
+            .unwrap()
+            .simulated_accounts
+            .insert(address, account);
+    }
+
+    /// Result of the next simulation, its accounts are read from the simulated accounts
+    pub fn push_simulation_result(&self, simulation_result: SimulationResult) {
+        self.state
+            .lock()
+            .unwrap()
+            .simulation_results
+            .push_back(simulation_result);
+    }
+
+    pub fn set_block_height(&self, block_height: u64) {
+        self.state.lock().unwrap().block_height = block_height;
+    }
+
+    pub fn set_signature_status(&self, signature: Signature, signature_status: SignatureStatus) {
+        self.state
+            .lock()
+            .unwrap()
+            .signature_statuses
+            .insert(signature, signature_status);
+    }
+
+    pub fn add_transaction(&self, signature: Signature, transaction: ConfirmedTransaction) {
+        self.state
+            .lock()
+            .unwrap()
+            .transactions
+            .insert(signature, transaction);
+    }
+
+    pub fn simulated_transactions(&self) -> Vec<VersionedTransaction> {
+        self.state.lock().unwrap().simulated_transactions.clone()
+    }
+
+    pub fn sent_transactions(&self) -> Vec<VersionedTransaction> {
+        self.state.lock().unwrap().sent_transactions.clone()
+    }
+}
+
+#[async_trait]
+impl SolanaRpc for InMemorySolanaRpc {
+    async fn get_address_lookup_tables(
+        &self,
+        addresses: &[Pubkey],
+    ) -> Result<Vec<AddressLookupTableAccount>> {
+        let state = self.state.lock().unwrap();
+        addresses
+            .iter()
+            .map(|address| {
+                state
+                    .address_lookup_tables
+                    .get(address)
+                    .cloned()
+                    .ok_or_else(|| RpcError::AccountNotFound(*address).into())
+            })
+            .collect()
+    }
+
+    async fn get_latest_blockhash(&self) -> Result<(Hash, u64)> {
+        Ok(self.state.lock().unwrap().latest_blockhash)
+    }
+
+    async fn get_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        let state = self.state.lock().unwrap();
+        Ok(addresses
+            .iter()
+            .map(|address| state.accounts.get(address).cloned())
+            .collect())
+    }
+
+    async fn simulate_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        accounts: &[Pubkey],
+    ) -> Result<SimulationResult> {
+        let mut state = self.state.lock().unwrap();
+        state.simulated_transactions.push(transaction.clone());
+        let mut simulation_result = state.simulation_results.pop_front().unwrap_or_default();
+        simulation_result.accounts = accounts
+            .iter()
+            .map(|address| {
+                state
+                    .simulated_accounts
+                    .get(address)
+                    .or_else(|| state.accounts.get(address))
+                    .cloned()
+            })
+            .collect();
+        Ok(simulation_result)
+    }
+
+    async fn send_transaction(&self, transaction: &VersionedTransaction) -> Result<Signature> {
+        let mut state = self.state.lock().unwrap();
+        state.sent_transactions.push(transaction.clone());
+        Ok(transaction.signatures.first().copied().unwrap_or_default())
+    }
+
+    async fn get_block_height(&self) -> Result<u64> {
+        Ok(self.state.lock().unwrap().block_height)
+    }
+
+    async fn get_signature_status(&self, signature: &Signature) -> Result<Option<SignatureStatus>> {
+        Ok(self
+            .state
+            .lock()
+            .unwrap()
+            .signature_statuses
+            .get(signature)
+            .cloned())
+    }
+
+    async fn get_transaction(&self, signature: &Signature) -> Result<ConfirmedTransaction> {
+        self.state
+            .lock()
+            .unwrap()
+            .transactions
+            .get(signature)
+            .cloned()
+            .ok_or_else(|| RpcError::TransactionNotFound(*signature).into())
+    }
+}
@@ -0,0 +1,220 @@
+//! RPC access needed to resolve, simulate and send swap transactions
+//!
+
+use async_trait::async_trait;
+use solana_sdk::{
+    account::Account,
+    address_lookup_table_account::AddressLookupTableAccount,
+    hash::Hash,
+    instruction::CompiledInstruction,
+    message::v0::LoadedAddresses,
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::{TransactionError, VersionedTransaction},
+};
+
+use thiserror::Error;
+
+use crate::{programs::NATIVE_MINT, Result};
+
+mod in_memory;
+#[cfg(feature = "rpc-client")]
+mod rpc_client;
+
+pub use in_memory::InMemorySolanaRpc;
+
+/// Size of the metadata preceding the addresses in an address lookup table account
+const LOOKUP_TABLE_META_SIZE: usize = 56;
+/// Discriminator of an initialized address lookup table
+const LOOKUP_TABLE_DISCRIMINATOR: u32 = 1;
+
+#[derive(Error, Debug)]
+pub enum RpcError {
+    /// Error of the underlying RPC client
+    #[error("rpc error: {0}")]
+    Client(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("account {0} not found")]
+    AccountNotFound(Pubkey),
+    #[error("account {0} is not an address lookup table")]
+    InvalidAddressLookupTable(Pubkey),
+    #[error("transaction {0} not found")]
+    TransactionNotFound(Signature),
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SimulationResult {
+    /// Error the transaction failed with, if any
+    pub err: Option<TransactionError>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+    /// State of the requested accounts after the simulation, in order
+    pub accounts: Vec<Option<Account>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignatureStatus {
+    pub slot: u64,
+    /// Error the transaction failed with, if any
+    pub err: Option<TransactionError>,
+    /// The transaction reached the commitment of the RPC
+    pub confirmed: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenBalance {
+    pub account_index: u8,
+    pub mint: Pubkey,
+    pub owner: Option<Pubkey>,
+    pub amount: u64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InnerInstructions {
+    /// Index of the top level instruction that invoked these instructions
+    pub index: u8,
+    pub instructions: Vec<CompiledInstruction>,
+}
+
+/// A confirmed transaction with the parts of its status meta used by this crate
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfirmedTransaction {
+    pub slot: u64,
+    pub transaction: VersionedTransaction,
+    pub loaded_addresses: LoadedAddresses,
+    pub err: Option<TransactionError>,
+    pub fee: u64,
+    pub pre_balances: Vec<u64>,
+    pub post_balances: Vec<u64>,
+    pub pre_token_balances: Vec<TokenBalance>,
+    pub post_token_balances: Vec<TokenBalance>,
+    pub log_messages: Vec<String>,
+    pub inner_instructions: Vec<InnerInstructions>,
+}
+
+impl ConfirmedTransaction {
+    /// Static account keys followed by the writable then readonly loaded addresses
+    pub fn account_keys(&self) -> Vec<Pubkey> {
+        self.transaction
+            .message
+            .static_account_keys()
+            .iter()
+            .chain(&self.loaded_addresses.writable)
+            .chain(&self.loaded_addresses.readonly)
+            .copied()
+            .collect()
+    }
+
+    /// Change of the balance of `mint` over the token accounts owned by `owner`
+    pub fn token_balance_change(&self, owner: &Pubkey, mint: &Pubkey) -> i128 {
+        let total = |balances: &[TokenBalance]| -> i128 {
+            balances
+                .iter()
+                .filter(|balance| &balance.mint == mint && balance.owner.as_ref() == Some(owner))
+                .map(|balance| balance.amount as i128)
+                .sum()
+        };
+        total(&self.post_token_balances) - total(&self.pre_token_balances)
+    }
+
+    /// Change of the balance of `mint` for `owner`. Wrapped SOL is unwrapped by swaps, so a
+    /// wrapped SOL change is read from the owner's lamports, excluding the transaction fee and
+    /// the rent of the token accounts created for the owner. Tips and other SOL transfers are
+    /// still included, [`SwapResult`](crate::swap_result::SwapResult) reads SOL amounts from
+    /// the swap events instead
+    pub fn swap_balance_change(&self, owner: &Pubkey, mint: &Pubkey) -> i128 {
+        match self.token_balance_change(owner, mint) {
+            0 if mint == &NATIVE_MINT => {
+                let (fee_paid, rent_paid) = match self.account_keys().first() == Some(owner) {
+                    true => (self.fee as i128, self.created_token_accounts_rent(owner)),
+                    false => (0, 0),
+                };
+                self.lamport_change(owner) + fee_paid + rent_paid
+            }
+            change => change,
+        }
+    }
+
+    /// Lamports held by the token accounts of `owner` that did not exist before the transaction
+    fn created_token_accounts_rent(&self, owner: &Pubkey) -> i128 {
+        self.post_token_balances
+            .iter()
+            .filter(|balance| balance.owner.as_ref() == Some(owner))
+            .filter(|balance| {
+                !self
+                    .pre_token_balances
+                    .iter()
+                    .any(|pre_balance| pre_balance.account_index == balance.account_index)
+            })
+            .filter_map(|balance| self.post_balances.get(balance.account_index as usize))
+            .map(|lamports| *lamports as i128)
+            .sum()
+    }
+
+    /// Change of the lamports of `account`, transaction fee included when it is the fee payer
+    pub fn lamport_change(&self, account: &Pubkey) -> i128 {
+        self.account_keys()
+            .iter()
+            .position(|key| key == account)
+            .and_then(|index| {
+                Some((
+                    *self.pre_balances.get(index)?,
+                    *self.post_balances.get(index)?,
+                ))
+            })
+            .map_or(0, |(pre, post)| post as i128 - pre as i128)
+    }
+}
+
+#[async_trait]
+pub trait SolanaRpc: Send + Sync {
+    /// Fetch and decode the address lookup tables, in the order of `addresses`
+    async fn get_address_lookup_tables(
+        &self,
+        addresses: &[Pubkey],
+    ) -> Result<Vec<AddressLookupTableAccount>>;
+
+    /// Latest blockhash with its last valid block height
+    async fn get_latest_blockhash(&self) -> Result<(Hash, u64)>;
+
+    /// Accounts in the order of `addresses`, `None` when an account does not exist
+    async fn get_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Account>>>;
+
+    /// Simulate without verifying signatures, returning the state of `accounts` after the simulation
+    async fn simulate_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        accounts: &[Pubkey],
+    ) -> Result<SimulationResult>;
+
+    async fn send_transaction(&self, transaction: &VersionedTransaction) -> Result<Signature>;
+
+    async fn get_block_height(&self) -> Result<u64>;
+
+    /// `None` when the signature is not known to the cluster yet
+    async fn get_signature_status(&self, signature: &Signature) -> Result<Option<SignatureStatus>>;
+
+    async fn get_transaction(&self, signature: &Signature) -> Result<ConfirmedTransaction>;
+}
+
+/// Decode the addresses of an address lookup table account
+pub fn deserialize_address_lookup_table(
+    key: Pubkey,
+    data: &[u8],
+) -> Result<AddressLookupTableAccount> {
+    let invalid = || RpcError::InvalidAddressLookupTable(key);
+    let discriminator = data
+        .get(..4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(invalid)?;
+    let addresses = data.get(LOOKUP_TABLE_META_SIZE..).ok_or_else(invalid)?;
+    if discriminator != LOOKUP_TABLE_DISCRIMINATOR || addresses.len() % 32 != 0 {
+        return Err(invalid().into());
+    }
+    Ok(AddressLookupTableAccount {
+        key,
+        addresses: addresses
+            .chunks_exact(32)
+            .map(|address| Pubkey::new_from_array(address.try_into().unwrap()))
+            .collect(),
+    })
+}
@@ -0,0 +1,221 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{
+        RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig, RpcTransactionConfig,
+    },
+};
+use solana_sdk::{
+    account::Account, address_lookup_table_account::AddressLookupTableAccount, bs58, hash::Hash,
+    instruction::CompiledInstruction, message::v0::LoadedAddresses, pubkey::Pubkey,
+    signature::Signature, transaction::VersionedTransaction,
+};
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, UiInstruction, UiLoadedAddresses, UiTransactionEncoding,
+    UiTransactionTokenBalance,
+};
+
+use super::{
+    deserialize_address_lookup_table, ConfirmedTransaction, InnerInstructions, RpcError,
+    SignatureStatus, SimulationResult, SolanaRpc, TokenBalance,
+};
+use crate::{error::JupiterError, Result};
+
+fn rpc_error<E: std::error::Error + Send + Sync + 'static>(error: E) -> JupiterError {
+    RpcError::Client(Box::new(error)).into()
+}
+
+fn parse_pubkey(pubkey: &str) -> Result<Pubkey> {
+    Pubkey::from_str(pubkey).map_err(rpc_error)
+}
+
+fn token_balances(
+    balances: OptionSerializer<Vec<UiTransactionTokenBalance>>,
+) -> Result<Vec<TokenBalance>> {
+    Option::<Vec<_>>::from(balances)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|balance: UiTransactionTokenBalance| {
+            Ok(TokenBalance {
+                account_index: balance.account_index,
+                mint: parse_pubkey(&balance.mint)?,
+                owner: Option::<String>::from(balance.owner)
+                    .map(|owner| parse_pubkey(&owner))
+                    .transpose()?,
+                amount: balance.ui_token_amount.amount.parse().map_err(rpc_error)?,
+            })
+        })
+        .collect()
+}
+
+#[async_trait]
+impl SolanaRpc for RpcClient {
+    async fn get_address_lookup_tables(
+        &self,
+        addresses: &[Pubkey],
+    ) -> Result<Vec<AddressLookupTableAccount>> {
+        let accounts = self
+            .get_multiple_accounts(addresses)
+            .await
+            .map_err(rpc_error)?;
+        addresses
+            .iter()
+            .zip(accounts)
+            .map(|(address, account)| {
+                let account = account.ok_or(RpcError::AccountNotFound(*address))?;
+                deserialize_address_lookup_table(*address, &account.data)
+            })
+            .collect()
+    }
+
+    async fn get_latest_blockhash(&self) -> Result<(Hash, u64)> {
+        self.get_latest_blockhash_with_commitment(self.commitment())
+            .await
+            .map_err(rpc_error)
+    }
+
+    async fn get_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        self.get_multiple_accounts(addresses)
+            .await
+            .map_err(rpc_error)
+    }
+
+    async fn simulate_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+        accounts: &[Pubkey],
+    ) -> Result<SimulationResult> {
+        let result = self
+            .simulate_transaction_with_config(
+                transaction,
+                RpcSimulateTransactionConfig {
+                    commitment: Some(self.commitment()),
+                    accounts: Some(RpcSimulateTransactionAccountsConfig {
+                        encoding: Some(UiAccountEncoding::Base64),
+                        addresses: accounts.iter().map(Pubkey::to_string).collect(),
+                    }),
+                    ..RpcSimulateTransactionConfig::default()
+                },
+            )
+            .await
+            .map_err(rpc_error)?
+            .value;
+        Ok(SimulationResult {
+            err: result.err,
+            logs: result.logs.unwrap_or_default(),
+            units_consumed: result.units_consumed,
+            accounts: result
+                .accounts
+                .unwrap_or_default()
+                .into_iter()
+                .map(|account| account.and_then(|account| account.decode()))
+                .collect(),
+        })
+    }
+
+    async fn send_transaction(&self, transaction: &VersionedTransaction) -> Result<Signature> {
+        RpcClient::send_transaction(self, transaction)
+            .await
+            .map_err(rpc_error)
+    }
+
+    async fn get_block_height(&self) -> Result<u64> {
+        RpcClient::get_block_height(self).await.map_err(rpc_error)
+    }
+
+    async fn get_signature_status(&self, signature: &Signature) -> Result<Option<SignatureStatus>> {
+        let mut statuses = self
+            .get_signature_statuses(&[*signature])
+            .await
+            .map_err(rpc_error)?
+            .value;
+        Ok(statuses.pop().flatten().map(|status| SignatureStatus {
+            slot: status.slot,
+            confirmed: status.satisfies_commitment(self.commitment()),
+            err: status.err,
+        }))
+    }
+
+    async fn get_transaction(&self, signature: &Signature) -> Result<ConfirmedTransaction> {
+        let confirmed_transaction = self
+            .get_transaction_with_config(
+                signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    commitment: Some(self.commitment()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await
+            .map_err(rpc_error)?;
+        let transaction = confirmed_transaction
+            .transaction
+            .transaction
+            .decode()
+            .ok_or(RpcError::TransactionNotFound(*signature))?;
+        let meta = confirmed_transaction
+            .transaction
+            .meta
+            .ok_or(RpcError::TransactionNotFound(*signature))?;
+
+        let loaded_addresses = match Option::<UiLoadedAddresses>::from(meta.loaded_addresses) {
+            Some(loaded_addresses) => LoadedAddresses {
+                writable: loaded_addresses
+                    .writable
+                    .iter()
+                    .map(|address| parse_pubkey(address))
+                    .collect::<Result<_>>()?,
+                readonly: loaded_addresses
+                    .readonly
+                    .iter()
+                    .map(|address| parse_pubkey(address))
+                    .collect::<Result<_>>()?,
+            },
+            None => LoadedAddresses::default(),
+        };
+        let inner_instructions = Option::<Vec<_>>::from(meta.inner_instructions)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|inner_instructions| {
+                let instructions = inner_instructions
+                    .instructions
+                    .into_iter()
+                    .filter_map(|instruction| match instruction {
+                        UiInstruction::Compiled(instruction) => Some(instruction),
+                        UiInstruction::Parsed(_) => None,
+                    })
+                    .map(|instruction| {
+                        Ok(CompiledInstruction {
+                            program_id_index: instruction.program_id_index,
+                            accounts: instruction.accounts,
+                            data: bs58::decode(&instruction.data)
+                                .into_vec()
+                                .map_err(rpc_error)?,
+                        })
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(InnerInstructions {
+                    index: inner_instructions.index,
+                    instructions,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(ConfirmedTransaction {
+            slot: confirmed_transaction.slot,
+            transaction,
+            loaded_addresses,
+            err: meta.err,
+            fee: meta.fee,
+            pre_balances: meta.pre_balances,
+            post_balances: meta.post_balances,
+            pre_token_balances: token_balances(meta.pre_token_balances)?,
+            post_token_balances: token_balances(meta.post_token_balances)?,
+            log_messages: Option::<Vec<_>>::from(meta.log_messages).unwrap_or_default(),
+            inner_instructions,
+        })
+    }
+}
@@ -0,0 +1,16 @@
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+pub fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    base64::encode(bytes).serialize(serializer)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    base64::decode(s).map_err(|e| de::Error::custom(format!("base64 decoding error: {:?}", e)))
+}
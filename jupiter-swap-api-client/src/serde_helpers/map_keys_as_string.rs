@@ -0,0 +1,31 @@
+use {
+    serde::{de, Deserializer, Serializer},
+    serde::{Deserialize, Serialize},
+    std::{collections::HashMap, hash::Hash, str::FromStr},
+};
+
+pub fn serialize<K, V, S>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    K: ToString,
+    V: Serialize,
+    S: Serializer,
+{
+    serializer.collect_map(map.iter().map(|(k, v)| (k.to_string(), v)))
+}
+
+pub fn deserialize<'de, K, V, D>(deserializer: D) -> Result<HashMap<K, V>, D::Error>
+where
+    K: FromStr + Eq + Hash,
+    V: Deserialize<'de>,
+    D: Deserializer<'de>,
+    <K as FromStr>::Err: std::fmt::Debug,
+{
+    HashMap::<String, V>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(k, v)| {
+            k.parse()
+                .map(|k| (k, v))
+                .map_err(|e| de::Error::custom(format!("Parse error: {:?}", e)))
+        })
+        .collect()
+}
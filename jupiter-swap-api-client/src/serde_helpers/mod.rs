@@ -1,2 +1,5 @@
+pub mod field_as_base64;
 pub mod field_as_string;
+pub mod map_keys_as_string;
 pub mod option_field_as_string;
+pub mod option_field_as_string_or_auto;
@@ -1,4 +1,8 @@
-use {serde::Serialize, serde::Serializer};
+use {
+    serde::{de, Deserializer, Serializer},
+    serde::{Deserialize, Serialize},
+    std::str::FromStr,
+};
 
 pub fn serialize<T, S>(t: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
 where
@@ -11,3 +15,19 @@ where
         serializer.serialize_none()
     }
 }
+
+/// `null` and empty strings are deserialized as `None`
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: FromStr,
+    D: Deserializer<'de>,
+    <T as FromStr>::Err: std::fmt::Debug,
+{
+    Option::<String>::deserialize(deserializer)?
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse()
+                .map_err(|e| de::Error::custom(format!("Parse error: {:?}", e)))
+        })
+        .transpose()
+}
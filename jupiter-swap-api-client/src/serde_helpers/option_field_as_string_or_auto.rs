@@ -0,0 +1,13 @@
+use {serde::Serialize, serde::Serializer};
+
+/// `None` is serialized as `"auto"`, to let the API pick the value
+pub fn serialize<T, S>(t: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ToString,
+    S: Serializer,
+{
+    match t {
+        Some(t) => t.to_string().serialize(serializer),
+        None => "auto".serialize(serializer),
+    }
+}
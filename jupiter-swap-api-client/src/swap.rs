@@ -1,10 +1,16 @@
 use crate::{
-    quote::QuoteResponse, serde_helpers::field_as_string, transaction_config::TransactionConfig,
+    quote::QuoteResponse,
+    serde_helpers::{field_as_base64, field_as_string},
+    transaction::{decode_and_sign, decode_transaction, SwapTransactionError},
+    transaction_config::TransactionConfig,
+    Result,
 };
 use serde::{Deserialize, Serialize};
 use solana_sdk::{
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
+    signer::signers::Signers,
+    transaction::{Transaction, VersionedTransaction},
 };
 
 #[derive(Serialize)]
@@ -17,25 +23,35 @@ pub struct SwapRequest {
     pub config: TransactionConfig,
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct SwapResponse {
-    #[serde(with = "base64_deserialize")]
+    #[serde(with = "field_as_base64")]
     pub swap_transaction: Vec<u8>,
     pub last_valid_block_height: u64,
 }
 
-mod base64_deserialize {
-    use super::*;
-    use serde::{de, Deserializer};
+impl SwapResponse {
+    /// Decode `swap_transaction`, a legacy transaction is decoded to a legacy message
+    pub fn versioned_transaction(&self) -> Result<VersionedTransaction> {
+        decode_transaction(&self.swap_transaction)
+    }
+
+    /// Decode `swap_transaction` requested with `as_legacy_transaction`
+    pub fn legacy_transaction(&self) -> Result<Transaction> {
+        self.versioned_transaction()?
+            .into_legacy_transaction()
+            .ok_or_else(|| SwapTransactionError::NotLegacyTransaction.into())
+    }
 
-    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let swap_transaction_string = String::deserialize(deserializer)?;
-        base64::decode(swap_transaction_string)
-            .map_err(|e| de::Error::custom(format!("base64 decoding error: {:?}", e)))
+    /// Decode `swap_transaction`, check that `user_public_key` is the fee payer and sign it with `signers`.
+    /// Signatures already present for other signers are kept
+    pub fn sign<T: Signers + ?Sized>(
+        &self,
+        user_public_key: &Pubkey,
+        signers: &T,
+    ) -> Result<VersionedTransaction> {
+        decode_and_sign(&self.swap_transaction, user_public_key, signers)
     }
 }
 
@@ -69,7 +85,7 @@ struct InstructionInternal {
     #[serde(with = "field_as_string")]
     pub program_id: Pubkey,
     pub accounts: Vec<AccountMetaInternal>,
-    #[serde(with = "base64_deserialize")]
+    #[serde(with = "field_as_base64")]
     pub data: Vec<u8>,
 }
 
@@ -82,12 +98,12 @@ pub struct AccountMetaInternal {
     pub is_writable: bool,
 }
 
-impl Into<AccountMeta> for AccountMetaInternal {
-    fn into(self) -> AccountMeta {
-        AccountMeta {
-            pubkey: self.pubkey,
-            is_signer: self.is_signer,
-            is_writable: self.is_writable,
+impl From<AccountMetaInternal> for AccountMeta {
+    fn from(value: AccountMetaInternal) -> Self {
+        Self {
+            pubkey: value.pubkey,
+            is_signer: value.is_signer,
+            is_writable: value.is_writable,
         }
     }
 }
@@ -96,12 +112,12 @@ impl Into<AccountMeta> for AccountMetaInternal {
 #[serde(rename_all = "camelCase")]
 struct PubkeyInternal(#[serde(with = "field_as_string")] Pubkey);
 
-impl Into<Instruction> for InstructionInternal {
-    fn into(self) -> Instruction {
-        Instruction {
-            program_id: self.program_id,
-            accounts: self.accounts.into_iter().map(Into::into).collect(),
-            data: self.data,
+impl From<InstructionInternal> for Instruction {
+    fn from(value: InstructionInternal) -> Self {
+        Self {
+            program_id: value.program_id,
+            accounts: value.accounts.into_iter().map(Into::into).collect(),
+            data: value.data,
         }
     }
 }
@@ -0,0 +1,267 @@
+//! Realized amounts of a landed swap, read from its confirmed transaction
+//!
+
+use rust_decimal::Decimal;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    executor::ExecutionError,
+    jupiter_instruction::JUPITER_PROGRAM_ID,
+    programs::NATIVE_MINT,
+    quote::{QuoteResponse, SwapMode},
+    route_plan_with_metadata::SwapInfo,
+    rpc::ConfirmedTransaction,
+    Result,
+};
+
+/// Prefix of the self CPI instructions used by `emit_cpi!` to emit events
+pub const EVENT_IX_TAG: [u8; 8] = [0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d];
+/// `sha256("event:SwapEvent")[..8]`
+pub const SWAP_EVENT_DISCRIMINATOR: [u8; 8] = [64, 198, 205, 232, 38, 8, 113, 226];
+/// `sha256("event:FeeEvent")[..8]`
+pub const FEE_EVENT_DISCRIMINATOR: [u8; 8] = [73, 79, 78, 127, 184, 213, 13, 220];
+
+const PROGRAM_DATA_LOG_PREFIX: &str = "Program data: ";
+
+/// Emitted by the Jupiter program for every AMM swapped through
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapEvent {
+    pub amm: Pubkey,
+    pub input_mint: Pubkey,
+    pub input_amount: u64,
+    pub output_mint: Pubkey,
+    pub output_amount: u64,
+}
+
+/// Emitted by the Jupiter program when a platform fee is collected
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeeEvent {
+    pub account: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JupiterEvent {
+    Swap(SwapEvent),
+    Fee(FeeEvent),
+}
+
+impl JupiterEvent {
+    /// Decode an event from its discriminator and borsh data, `None` for other data
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let (discriminator, mut data) = (data.get(..8)?, data.get(8..)?);
+        if discriminator == SWAP_EVENT_DISCRIMINATOR {
+            Some(Self::Swap(SwapEvent {
+                amm: read_pubkey(&mut data)?,
+                input_mint: read_pubkey(&mut data)?,
+                input_amount: read_u64(&mut data)?,
+                output_mint: read_pubkey(&mut data)?,
+                output_amount: read_u64(&mut data)?,
+            }))
+        } else if discriminator == FEE_EVENT_DISCRIMINATOR {
+            Some(Self::Fee(FeeEvent {
+                account: read_pubkey(&mut data)?,
+                mint: read_pubkey(&mut data)?,
+                amount: read_u64(&mut data)?,
+            }))
+        } else {
+            None
+        }
+    }
+
+    /// Events emitted through self CPI, or logged as `Program data:` by older program versions
+    pub fn parse(confirmed_transaction: &ConfirmedTransaction) -> Vec<Self> {
+        let account_keys = confirmed_transaction.account_keys();
+        let cpi_events = confirmed_transaction
+            .inner_instructions
+            .iter()
+            .flat_map(|inner_instructions| &inner_instructions.instructions)
+            .filter(|instruction| {
+                account_keys.get(instruction.program_id_index as usize) == Some(&JUPITER_PROGRAM_ID)
+            })
+            .filter_map(|instruction| {
+                instruction
+                    .data
+                    .strip_prefix(&EVENT_IX_TAG)
+                    .and_then(Self::decode)
+            })
+            .collect::<Vec<_>>();
+        if !cpi_events.is_empty() {
+            return cpi_events;
+        }
+        confirmed_transaction
+            .log_messages
+            .iter()
+            .filter_map(|log| log.strip_prefix(PROGRAM_DATA_LOG_PREFIX))
+            .filter_map(|data| base64::decode(data).ok())
+            .filter_map(|data| Self::decode(&data))
+            .collect()
+    }
+}
+
+fn read_pubkey(data: &mut &[u8]) -> Option<Pubkey> {
+    let pubkey = Pubkey::new_from_array(data.get(..32)?.try_into().ok()?);
+    *data = &data[32..];
+    Some(pubkey)
+}
+
+fn read_u64(data: &mut &[u8]) -> Option<u64> {
+    let value = u64::from_le_bytes(data.get(..8)?.try_into().ok()?);
+    *data = &data[8..];
+    Some(value)
+}
+
+/// Change of the balance of `mint` through the swaps, fees in `mint` deducted
+fn event_balance_change(hops: &[SwapEvent], fees: &[FeeEvent], mint: &Pubkey) -> i128 {
+    let received = hops
+        .iter()
+        .filter(|hop| &hop.output_mint == mint)
+        .map(|hop| hop.output_amount as i128)
+        .sum::<i128>();
+    let sent = hops
+        .iter()
+        .filter(|hop| &hop.input_mint == mint)
+        .map(|hop| hop.input_amount as i128)
+        .sum::<i128>();
+    let fees = fees
+        .iter()
+        .filter(|fee| &fee.mint == mint)
+        .map(|fee| fee.amount as i128)
+        .sum::<i128>();
+    received - sent - fees
+}
+
+/// Realized amounts of a landed swap
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SwapResult {
+    /// Swap events in execution order, one per hop
+    pub hops: Vec<SwapEvent>,
+    pub fees: Vec<FeeEvent>,
+    /// Input amount that left the user's accounts
+    pub in_amount: u64,
+    /// Output amount that reached the user's accounts
+    pub out_amount: u64,
+}
+
+impl SwapResult {
+    /// Parse the swap of `input_mint` into `output_mint` by `user_public_key`
+    pub fn parse(
+        confirmed_transaction: &ConfirmedTransaction,
+        user_public_key: &Pubkey,
+        input_mint: &Pubkey,
+        output_mint: &Pubkey,
+    ) -> Result<Self> {
+        if let Some(err) = &confirmed_transaction.err {
+            return Err(ExecutionError::TransactionFailed {
+                signature: confirmed_transaction
+                    .transaction
+                    .signatures
+                    .first()
+                    .copied()
+                    .unwrap_or_default(),
+                err: err.clone(),
+            }
+            .into());
+        }
+        let (hops, fees) = JupiterEvent::parse(confirmed_transaction).into_iter().fold(
+            (Vec::new(), Vec::new()),
+            |(mut hops, mut fees), event| {
+                match event {
+                    JupiterEvent::Swap(event) => hops.push(event),
+                    JupiterEvent::Fee(event) => fees.push(event),
+                }
+                (hops, fees)
+            },
+        );
+        // SOL balance changes also include rent and tips, the events only the swapped amounts
+        let change = |mint: &Pubkey| match mint == &NATIVE_MINT && !hops.is_empty() {
+            true => event_balance_change(&hops, &fees, mint),
+            false => confirmed_transaction.swap_balance_change(user_public_key, mint),
+        };
+        let amount = |mint: &Pubkey, change: i128| {
+            u64::try_from(change).map_err(|_| ExecutionError::UnexpectedBalanceChange {
+                mint: *mint,
+                change,
+            })
+        };
+        Ok(Self {
+            in_amount: amount(input_mint, -change(input_mint))?,
+            out_amount: amount(output_mint, change(output_mint))?,
+            hops,
+            fees,
+        })
+    }
+
+    /// Slippage from the quoted amounts in bps, positive when worse than quoted.
+    /// `None` when the quoted amount is zero
+    pub fn slippage_bps(&self, quote_response: &QuoteResponse) -> Option<Decimal> {
+        let (quoted, realized, sign) = match quote_response.swap_mode {
+            SwapMode::ExactIn => (quote_response.out_amount, self.out_amount, Decimal::ONE),
+            SwapMode::ExactOut => (
+                quote_response.in_amount,
+                self.in_amount,
+                Decimal::NEGATIVE_ONE,
+            ),
+        };
+        let quoted = Decimal::from(quoted);
+        let difference = (quoted - Decimal::from(realized)) * sign;
+        difference
+            .checked_mul(Decimal::from(10_000))?
+            .checked_div(quoted)
+    }
+
+    /// Pair every hop with the step of `quote_response.route_plan` swapping through the same AMM
+    pub fn reconcile(&self, quote_response: &QuoteResponse) -> SwapReconciliation {
+        let mut used = vec![false; quote_response.route_plan.len()];
+        let hops = self
+            .hops
+            .iter()
+            .map(|event| {
+                let quoted = quote_response
+                    .route_plan
+                    .iter()
+                    .enumerate()
+                    .find(|(index, step)| {
+                        !used[*index]
+                            && step.swap_info.amm_key == event.amm
+                            && step.swap_info.input_mint == event.input_mint
+                    })
+                    .map(|(index, step)| {
+                        used[index] = true;
+                        step.swap_info.clone()
+                    });
+                HopFill {
+                    event: event.clone(),
+                    quoted,
+                }
+            })
+            .collect();
+        SwapReconciliation {
+            quoted_in_amount: quote_response.in_amount,
+            in_amount: self.in_amount,
+            quoted_out_amount: quote_response.out_amount,
+            out_amount: self.out_amount,
+            slippage_bps: self.slippage_bps(quote_response),
+            hops,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HopFill {
+    pub event: SwapEvent,
+    /// `None` when the hop is not in the quoted route plan
+    pub quoted: Option<SwapInfo>,
+}
+
+/// Quoted against realized amounts of a swap
+#[derive(Clone, Debug, PartialEq)]
+pub struct SwapReconciliation {
+    pub quoted_in_amount: u64,
+    pub in_amount: u64,
+    pub quoted_out_amount: u64,
+    pub out_amount: u64,
+    pub slippage_bps: Option<Decimal>,
+    pub hops: Vec<HopFill>,
+}
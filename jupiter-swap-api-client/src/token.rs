@@ -0,0 +1,143 @@
+//! Token API data structures, metadata of the tokens tradable on Jupiter
+//!
+
+use std::{collections::HashMap, fmt};
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    amount,
+    serde_helpers::{field_as_string, option_field_as_string},
+};
+
+/// Tag of a token, several tags can be requested at once
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TokenTag {
+    Verified,
+    /// Tokens of the strict list
+    Strict,
+    /// Liquid staking tokens
+    Lst,
+    Community,
+    Unknown,
+    Token2022,
+    Pump,
+    Moonshot,
+    BirdeyeTrending,
+    Clone,
+    /// Tag not known to this client
+    Other(String),
+}
+
+impl TokenTag {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Verified => "verified",
+            Self::Strict => "strict",
+            Self::Lst => "lst",
+            Self::Community => "community",
+            Self::Unknown => "unknown",
+            Self::Token2022 => "token-2022",
+            Self::Pump => "pump",
+            Self::Moonshot => "moonshot",
+            Self::BirdeyeTrending => "birdeye-trending",
+            Self::Clone => "clone",
+            Self::Other(tag) => tag,
+        }
+    }
+}
+
+impl From<&str> for TokenTag {
+    fn from(tag: &str) -> Self {
+        match tag {
+            "verified" => Self::Verified,
+            "strict" => Self::Strict,
+            "lst" => Self::Lst,
+            "community" => Self::Community,
+            "unknown" => Self::Unknown,
+            "token-2022" => Self::Token2022,
+            "pump" => Self::Pump,
+            "moonshot" => Self::Moonshot,
+            "birdeye-trending" => Self::BirdeyeTrending,
+            "clone" => Self::Clone,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for TokenTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for TokenTag {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for TokenTag {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(String::deserialize(deserializer)?.as_str().into())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TokenInfo {
+    #[serde(with = "field_as_string")]
+    pub address: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    #[serde(rename = "logoURI")]
+    pub logo_uri: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<TokenTag>,
+    pub daily_volume: Option<f64>,
+    /// RFC 3339 timestamp of when the token was first seen
+    pub created_at: Option<String>,
+    /// `None` when the mint authority is revoked and the supply is fixed
+    #[serde(default, with = "option_field_as_string")]
+    pub mint_authority: Option<Pubkey>,
+    /// `None` when token accounts cannot be frozen
+    #[serde(default, with = "option_field_as_string")]
+    pub freeze_authority: Option<Pubkey>,
+    /// Token-2022 permanent delegate, able to transfer or burn from any account
+    #[serde(default, with = "option_field_as_string")]
+    pub permanent_delegate: Option<Pubkey>,
+    pub minted_at: Option<String>,
+    /// Additional metadata, e.g. `coingeckoId`
+    #[serde(default)]
+    pub extensions: HashMap<String, serde_json::Value>,
+}
+
+impl TokenInfo {
+    pub fn has_tag(&self, tag: &TokenTag) -> bool {
+        self.tags.contains(tag)
+    }
+
+    pub fn has_mint_authority(&self) -> bool {
+        self.mint_authority.is_some()
+    }
+
+    pub fn has_freeze_authority(&self) -> bool {
+        self.freeze_authority.is_some()
+    }
+
+    /// `amount` in human readable units, e.g. `QuoteResponse::out_amount` of 1_500_000 is 1.5 USDC,
+    /// see [`amount::ui_amount`]
+    pub fn ui_amount(&self, amount: u64) -> Option<Decimal> {
+        amount::ui_amount(amount, self.decimals)
+    }
+
+    /// Amount in base units of a human readable amount, rounded down, see [`amount::base_amount`]
+    pub fn amount(&self, ui_amount: Decimal) -> Option<u64> {
+        amount::base_amount(ui_amount, self.decimals)
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct TradableMint(#[serde(with = "field_as_string")] pub Pubkey);
@@ -0,0 +1,122 @@
+//! Decoding and signing of the transactions returned by the API
+//!
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    message::CompileError,
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::{signers::Signers, SignerError},
+    transaction::VersionedTransaction,
+};
+use thiserror::Error;
+
+use crate::{serde_helpers::field_as_base64, Result};
+
+#[derive(Error, Debug)]
+pub enum SwapTransactionError {
+    /// A transaction could not be decoded from or encoded to its wire format
+    #[error("failed to encode or decode transaction: {0}")]
+    Encoding(#[source] bincode::Error),
+    /// A legacy transaction was expected but a versioned one was returned
+    #[error("transaction is not a legacy transaction")]
+    NotLegacyTransaction,
+    /// The fee payer of the transaction is not the requested user public key
+    #[error("unexpected fee payer, expected: {expected}, found: {found:?}")]
+    UnexpectedFeePayer {
+        expected: Pubkey,
+        found: Option<Pubkey>,
+    },
+    /// A signer was given that the transaction does not require
+    #[error("{0} is not a required signer of the transaction")]
+    SignerNotRequired(Pubkey),
+    #[error("failed to sign transaction: {0}")]
+    Signer(#[from] SignerError),
+    /// `address_lookup_table_addresses` references a table that was not provided
+    #[error("address lookup table {0} was not provided")]
+    MissingAddressLookupTable(Pubkey),
+    #[error("failed to compile message: {0}")]
+    Compile(#[from] CompileError),
+    /// The serialized transaction does not fit in a packet
+    #[error("transaction too large: {size} bytes, max: {max_size} bytes")]
+    TransactionTooLarge { size: usize, max_size: usize },
+}
+
+/// Decode a bincode serialized transaction, legacy transactions are decoded to a legacy message
+pub fn decode_transaction(bytes: &[u8]) -> Result<VersionedTransaction> {
+    bincode::deserialize(bytes).map_err(|e| SwapTransactionError::Encoding(e).into())
+}
+
+/// Check that the fee payer, which is the first required signer, is `user_public_key`
+pub fn check_fee_payer(transaction: &VersionedTransaction, user_public_key: &Pubkey) -> Result<()> {
+    match transaction.message.static_account_keys().first() {
+        Some(fee_payer) if fee_payer == user_public_key => Ok(()),
+        fee_payer => Err(SwapTransactionError::UnexpectedFeePayer {
+            expected: *user_public_key,
+            found: fee_payer.copied(),
+        }
+        .into()),
+    }
+}
+
+/// Sign with `signers`, keeping the signatures already present for the other required signers
+pub fn sign_transaction<T: Signers + ?Sized>(
+    transaction: &mut VersionedTransaction,
+    signers: &T,
+) -> Result<()> {
+    let num_required_signatures = transaction.message.header().num_required_signatures as usize;
+    let required_signers = &transaction.message.static_account_keys()[..num_required_signatures];
+    let positions = signers
+        .pubkeys()
+        .iter()
+        .map(|pubkey| {
+            required_signers
+                .iter()
+                .position(|required_signer| required_signer == pubkey)
+                .ok_or_else(|| SwapTransactionError::SignerNotRequired(*pubkey).into())
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let signatures = signers
+        .try_sign_message(&transaction.message.serialize())
+        .map_err(SwapTransactionError::from)?;
+    transaction
+        .signatures
+        .resize(num_required_signatures, Signature::default());
+    for (position, signature) in positions.into_iter().zip(signatures) {
+        transaction.signatures[position] = signature;
+    }
+    Ok(())
+}
+
+/// Decode a transaction returned by the API, check that `user_public_key` is the fee payer and sign it with `signers`
+pub fn decode_and_sign<T: Signers + ?Sized>(
+    bytes: &[u8],
+    user_public_key: &Pubkey,
+    signers: &T,
+) -> Result<VersionedTransaction> {
+    let mut transaction = decode_transaction(bytes)?;
+    check_fee_payer(&transaction, user_public_key)?;
+    sign_transaction(&mut transaction, signers)?;
+    Ok(transaction)
+}
+
+/// Base64 encoded transaction returned by the API, e.g. by the limit order API
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(transparent)]
+pub struct EncodedTransaction(#[serde(with = "field_as_base64")] pub Vec<u8>);
+
+impl EncodedTransaction {
+    pub fn versioned_transaction(&self) -> Result<VersionedTransaction> {
+        decode_transaction(&self.0)
+    }
+
+    /// Decode, check that `user_public_key` is the fee payer and sign with `signers`
+    pub fn sign<T: Signers + ?Sized>(
+        &self,
+        user_public_key: &Pubkey,
+        signers: &T,
+    ) -> Result<VersionedTransaction> {
+        decode_and_sign(&self.0, user_public_key, signers)
+    }
+}
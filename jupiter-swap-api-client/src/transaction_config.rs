@@ -3,7 +3,7 @@ use solana_sdk::pubkey::Pubkey;
 
 use crate::serde_helpers::option_field_as_string;
 
-#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[serde(untagged)]
 pub enum ComputeUnitPriceMicroLamports {
@@ -12,7 +12,7 @@ pub enum ComputeUnitPriceMicroLamports {
     Auto,
 }
 
-#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 // #[serde(untagged)]
 pub enum PrioritizationFeeLamports {
@@ -39,7 +39,7 @@ where
     Ok(())
 }
 
-#[derive(Serialize, Debug, PartialEq)]
+#[derive(Serialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[serde(default)]
 pub struct TransactionConfig {
@@ -0,0 +1,195 @@
+//! Ultra API data structures, an order is signed by the taker and executed by the API
+//!
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    pubkey::Pubkey, signature::Signature, signer::signers::Signers,
+    transaction::VersionedTransaction,
+};
+use thiserror::Error;
+
+use crate::{
+    quote::SwapMode,
+    route_plan_with_metadata::RoutePlanWithMetadata,
+    serde_helpers::{field_as_string, option_field_as_string},
+    transaction::{check_fee_payer, sign_transaction, EncodedTransaction, SwapTransactionError},
+    Result,
+};
+
+#[derive(Error, Debug)]
+pub enum UltraError {
+    /// An order was requested without a taker, so there is no transaction to sign
+    #[error("order has no transaction, a taker is required")]
+    MissingTransaction,
+    /// `/execute` did not land the order
+    #[error("ultra execute failed with code {code}: {}", error.as_deref().unwrap_or("unknown error"))]
+    ExecuteFailed {
+        code: i32,
+        error: Option<String>,
+        signature: Option<Signature>,
+    },
+}
+
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderRequest {
+    #[serde(with = "field_as_string")]
+    pub input_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub output_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub amount: u64,
+    /// Without a taker the order is only a quote and has no transaction
+    #[serde(
+        with = "option_field_as_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub taker: Option<Pubkey>,
+    #[serde(
+        with = "option_field_as_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub referral_account: Option<Pubkey>,
+    /// Referral fee in basis points
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub referral_fee: Option<u16>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum SwapType {
+    Aggregator,
+    /// Filled by a market maker quote, see `OrderResponse::quote_id`
+    Rfq,
+    Hashflow,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderResponse {
+    #[serde(with = "field_as_string")]
+    pub input_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub output_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub in_amount: u64,
+    #[serde(with = "field_as_string")]
+    pub out_amount: u64,
+    #[serde(with = "field_as_string")]
+    pub other_amount_threshold: u64,
+    pub swap_mode: SwapMode,
+    pub slippage_bps: u16,
+    pub price_impact_pct: String,
+    #[serde(default)]
+    pub route_plan: RoutePlanWithMetadata,
+    #[serde(default, with = "option_field_as_string")]
+    pub fee_mint: Option<Pubkey>,
+    #[serde(default)]
+    pub fee_bps: u16,
+    #[serde(default, with = "option_field_as_string")]
+    pub taker: Option<Pubkey>,
+    /// The transaction fees are paid by Jupiter, the taker is then not the fee payer
+    #[serde(default)]
+    pub gasless: bool,
+    /// Unsigned transaction, `None` without a taker
+    pub transaction: Option<EncodedTransaction>,
+    #[serde(default)]
+    pub prioritization_fee_lamports: u64,
+    /// Sent back with the signed transaction to `/execute`
+    pub request_id: String,
+    pub swap_type: SwapType,
+    /// Router that found the route, e.g. `iris` or `jupiterz`
+    pub router: Option<String>,
+    /// Market maker quote of an RFQ order
+    pub quote_id: Option<String>,
+    /// Market maker of an RFQ order, also required to sign
+    #[serde(default, with = "option_field_as_string")]
+    pub maker: Option<Pubkey>,
+    /// Unix timestamp in seconds after which an RFQ order can no longer be executed
+    #[serde(default, with = "option_field_as_string")]
+    pub expire_at: Option<i64>,
+    pub in_usd_value: Option<f64>,
+    pub out_usd_value: Option<f64>,
+    pub error_code: Option<i32>,
+    pub error_message: Option<String>,
+}
+
+impl OrderResponse {
+    /// Decode `transaction` and sign it with `signers`.
+    /// The taker must be the fee payer unless the order is gasless
+    pub fn sign<T: Signers + ?Sized>(&self, signers: &T) -> Result<VersionedTransaction> {
+        let (Some(transaction), Some(taker)) = (&self.transaction, &self.taker) else {
+            return Err(UltraError::MissingTransaction.into());
+        };
+        let mut transaction = transaction.versioned_transaction()?;
+        if !self.gasless {
+            check_fee_payer(&transaction, taker)?;
+        }
+        sign_transaction(&mut transaction, signers)?;
+        Ok(transaction)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecuteRequest {
+    pub signed_transaction: EncodedTransaction,
+    pub request_id: String,
+}
+
+impl ExecuteRequest {
+    pub fn new(signed_transaction: &VersionedTransaction, request_id: String) -> Result<Self> {
+        Ok(Self {
+            signed_transaction: EncodedTransaction(
+                bincode::serialize(signed_transaction).map_err(SwapTransactionError::Encoding)?,
+            ),
+            request_id,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ExecuteStatus {
+    Success,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecuteResponse {
+    pub status: ExecuteStatus,
+    /// Present once the transaction was sent, even if it failed
+    #[serde(default, with = "option_field_as_string")]
+    pub signature: Option<Signature>,
+    #[serde(default, with = "option_field_as_string")]
+    pub slot: Option<u64>,
+    /// 0 on success
+    #[serde(default)]
+    pub code: i32,
+    pub error: Option<String>,
+    #[serde(default, with = "option_field_as_string")]
+    pub total_input_amount: Option<u64>,
+    #[serde(default, with = "option_field_as_string")]
+    pub total_output_amount: Option<u64>,
+    #[serde(default, with = "option_field_as_string")]
+    pub input_amount_result: Option<u64>,
+    #[serde(default, with = "option_field_as_string")]
+    pub output_amount_result: Option<u64>,
+}
+
+impl ExecuteResponse {
+    /// Signature of the landed transaction, or [`UltraError::ExecuteFailed`]
+    pub fn into_result(self) -> Result<Signature> {
+        match (self.status, self.signature) {
+            (ExecuteStatus::Success, Some(signature)) => Ok(signature),
+            (_, signature) => Err(UltraError::ExecuteFailed {
+                code: self.code,
+                error: self.error,
+                signature,
+            }
+            .into()),
+        }
+    }
+}
@@ -0,0 +1,477 @@
+//! Check a swap transaction built by the API against the request it was built for, before signing it
+//!
+
+use std::collections::HashSet;
+
+use solana_sdk::{
+    address_lookup_table_account::AddressLookupTableAccount, compute_budget,
+    instruction::Instruction, message::VersionedMessage, pubkey::Pubkey,
+    system_instruction::SystemInstruction, system_program, transaction::VersionedTransaction,
+};
+use thiserror::Error;
+
+use crate::{
+    amount::{amount_after_slippage, amount_with_slippage},
+    jupiter_instruction::{RouteAmounts, RouteArgs, JUPITER_PROGRAM_ID},
+    programs::{
+        associated_token_address, ASSOCIATED_TOKEN_PROGRAM_ID, NATIVE_MINT, TOKEN_2022_PROGRAM_ID,
+        TOKEN_PROGRAM_ID,
+    },
+    swap::{SwapInstructionsResponse, SwapRequest, SwapResponse},
+    transaction::check_fee_payer,
+    Result,
+};
+
+/// `sha256("global:set_token_ledger")[..8]`, the only Jupiter instruction allowed besides the route
+pub const SET_TOKEN_LEDGER_DISCRIMINATOR: [u8; 8] = [228, 85, 185, 112, 78, 79, 77, 2];
+
+/// Token and Token-2022 instruction tags
+const CLOSE_ACCOUNT_TAG: u8 = 9;
+const SYNC_NATIVE_TAG: u8 = 17;
+/// Associated Token Account instruction tag
+const CREATE_IDEMPOTENT_TAG: u8 = 1;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum VerificationError {
+    #[error("{0} is required to sign, only the user may sign")]
+    UnexpectedSigner(Pubkey),
+    #[error("program {0} is not allowed")]
+    ProgramNotAllowed(Pubkey),
+    /// A Jupiter instruction that is neither a route nor `set_token_ledger`
+    #[error("unexpected Jupiter program instruction")]
+    UnexpectedJupiterInstruction,
+    #[error("transaction has no Jupiter route instruction")]
+    MissingRouteInstruction,
+    #[error("transaction has more than one Jupiter route instruction")]
+    MultipleRouteInstructions,
+    #[error("{field} is {found} in the route instruction, expected {expected}")]
+    AmountMismatch {
+        field: &'static str,
+        expected: u64,
+        found: u64,
+    },
+    /// The slippage of the route instruction accepts a worse amount than `other_amount_threshold`
+    #[error("route instruction accepts {found}, beyond the quoted threshold {expected}")]
+    SlippageBoundExceeded { expected: u64, found: u64 },
+    /// The route instruction moves tokens on behalf of another authority than the user
+    #[error("route instruction transfer authority is {0}, expected the user")]
+    UnexpectedTransferAuthority(Pubkey),
+    /// The route instruction sends the output to another account than the user's associated
+    /// token account or the requested `destination_token_account`
+    #[error("route instruction output goes to {0}, expected the user's token account")]
+    UnexpectedDestination(Pubkey),
+    #[error("platform fee is {found} bps in the route instruction, expected {expected} bps")]
+    PlatformFeeMismatch { expected: u8, found: u8 },
+    #[error("route instruction has no {0} account")]
+    MissingAccount(&'static str),
+    #[error("unexpected transfer of {lamports} lamports to {destination}")]
+    UnexpectedTransfer { destination: Pubkey, lamports: u64 },
+    #[error("unexpected system program instruction")]
+    UnexpectedSystemInstruction,
+    /// Only idempotent associated token account creation, closing to the user and syncing
+    /// the user's wrapped SOL account are allowed
+    #[error("unexpected instruction of token program {0}")]
+    UnexpectedTokenInstruction(Pubkey),
+    #[error("token account closed to {0}, expected the user")]
+    UnexpectedCloseDestination(Pubkey),
+    /// An account referenced through an address lookup table that was not provided
+    #[error("account {0} of the message could not be resolved")]
+    UnresolvedAccount(usize),
+}
+
+/// Allows by default the Jupiter aggregator, Token, Token-2022, Associated Token Account,
+/// ComputeBudget and System programs, and SOL transfers to the user's wrapped SOL account
+pub struct SwapVerifier<'a> {
+    swap_request: &'a SwapRequest,
+    wrapped_sol_account: Pubkey,
+    allowed_programs: HashSet<Pubkey>,
+    allowed_transfer_destinations: HashSet<Pubkey>,
+    address_lookup_table_accounts: Vec<AddressLookupTableAccount>,
+}
+
+/// Instruction whose accounts are resolved, or left as their index in the message
+struct VerifiedInstruction<'d> {
+    program_id: Pubkey,
+    accounts: Vec<std::result::Result<Pubkey, usize>>,
+    data: &'d [u8],
+}
+
+impl<'a> SwapVerifier<'a> {
+    pub fn new(swap_request: &'a SwapRequest) -> Self {
+        let user = swap_request.user_public_key;
+        let wrapped_sol_account = associated_token_address(&user, &NATIVE_MINT, &TOKEN_PROGRAM_ID);
+        Self {
+            swap_request,
+            wrapped_sol_account,
+            allowed_programs: HashSet::from([
+                JUPITER_PROGRAM_ID,
+                TOKEN_PROGRAM_ID,
+                TOKEN_2022_PROGRAM_ID,
+                ASSOCIATED_TOKEN_PROGRAM_ID,
+                compute_budget::id(),
+                system_program::id(),
+            ]),
+            allowed_transfer_destinations: HashSet::from([wrapped_sol_account]),
+            address_lookup_table_accounts: Vec::new(),
+        }
+    }
+
+    pub fn allow_program(mut self, program_id: Pubkey) -> Self {
+        self.allowed_programs.insert(program_id);
+        self
+    }
+
+    /// Allow SOL transfers to `destination`, e.g. a Jito tip account with `JitoTipLamports`
+    pub fn allow_transfer_to(mut self, destination: Pubkey) -> Self {
+        self.allowed_transfer_destinations.insert(destination);
+        self
+    }
+
+    /// Resolve the accounts of a v0 transaction loaded from address lookup tables
+    pub fn address_lookup_table_accounts(
+        mut self,
+        address_lookup_table_accounts: Vec<AddressLookupTableAccount>,
+    ) -> Self {
+        self.address_lookup_table_accounts = address_lookup_table_accounts;
+        self
+    }
+
+    /// Decode `swap_transaction` and verify it, returning the unsigned transaction
+    pub fn verify_swap_response(
+        &self,
+        swap_response: &SwapResponse,
+    ) -> Result<VersionedTransaction> {
+        let transaction = swap_response.versioned_transaction()?;
+        self.verify_transaction(&transaction)?;
+        Ok(transaction)
+    }
+
+    pub fn verify_transaction(&self, transaction: &VersionedTransaction) -> Result<()> {
+        let message = &transaction.message;
+        let static_account_keys = message.static_account_keys();
+        let num_required_signatures = message.header().num_required_signatures as usize;
+        check_fee_payer(transaction, &self.swap_request.user_public_key)?;
+        if let Some(signer) = static_account_keys
+            .iter()
+            .take(num_required_signatures)
+            .find(|signer| **signer != self.swap_request.user_public_key)
+        {
+            return Err(VerificationError::UnexpectedSigner(*signer).into());
+        }
+
+        let account_keys = self.account_keys(message);
+        let instructions = message
+            .instructions()
+            .iter()
+            .map(|instruction| {
+                // Programs cannot be loaded from lookup tables
+                let program_id_index = instruction.program_id_index as usize;
+                let program_id = static_account_keys
+                    .get(program_id_index)
+                    .copied()
+                    .ok_or(VerificationError::UnresolvedAccount(program_id_index))?;
+                Ok(VerifiedInstruction {
+                    program_id,
+                    accounts: instruction
+                        .accounts
+                        .iter()
+                        .map(|index| {
+                            let index = *index as usize;
+                            account_keys.get(index).copied().flatten().ok_or(index)
+                        })
+                        .collect(),
+                    data: &instruction.data,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.verify_instructions(&instructions)
+    }
+
+    pub fn verify_swap_instructions(
+        &self,
+        swap_instructions: &SwapInstructionsResponse,
+    ) -> Result<()> {
+        let instructions = swap_instructions
+            .compute_budget_instructions
+            .iter()
+            .chain(&swap_instructions.setup_instructions)
+            .chain(&swap_instructions.token_ledger_instruction)
+            .chain([&swap_instructions.swap_instruction])
+            .chain(&swap_instructions.cleanup_instruction)
+            .collect::<Vec<&Instruction>>();
+        if let Some(signer) = instructions
+            .iter()
+            .flat_map(|instruction| &instruction.accounts)
+            .find(|account| {
+                account.is_signer && account.pubkey != self.swap_request.user_public_key
+            })
+        {
+            return Err(VerificationError::UnexpectedSigner(signer.pubkey).into());
+        }
+        let instructions = instructions
+            .into_iter()
+            .map(|instruction| VerifiedInstruction {
+                program_id: instruction.program_id,
+                accounts: instruction
+                    .accounts
+                    .iter()
+                    .map(|account| Ok(account.pubkey))
+                    .collect(),
+                data: &instruction.data,
+            })
+            .collect::<Vec<_>>();
+        self.verify_instructions(&instructions)
+    }
+
+    fn verify_instructions(&self, instructions: &[VerifiedInstruction]) -> Result<()> {
+        if let Some(instruction) = instructions
+            .iter()
+            .find(|instruction| !self.allowed_programs.contains(&instruction.program_id))
+        {
+            return Err(VerificationError::ProgramNotAllowed(instruction.program_id).into());
+        }
+
+        let mut route_instructions = Vec::new();
+        for instruction in instructions {
+            match instruction.program_id {
+                JUPITER_PROGRAM_ID => match RouteArgs::decode(instruction.data) {
+                    Some(route_args) => route_instructions.push((instruction, route_args)),
+                    None if instruction
+                        .data
+                        .starts_with(&SET_TOKEN_LEDGER_DISCRIMINATOR) => {}
+                    None => return Err(VerificationError::UnexpectedJupiterInstruction.into()),
+                },
+                TOKEN_PROGRAM_ID | TOKEN_2022_PROGRAM_ID => {
+                    self.verify_token_instruction(instruction)?
+                }
+                // Associated token accounts may only be created idempotently
+                ASSOCIATED_TOKEN_PROGRAM_ID if instruction.data != [CREATE_IDEMPOTENT_TAG] => {
+                    return Err(VerificationError::UnexpectedTokenInstruction(
+                        instruction.program_id,
+                    )
+                    .into())
+                }
+                program_id if program_id == system_program::id() => {
+                    self.verify_system_instruction(instruction)?
+                }
+                _ => {}
+            }
+        }
+
+        match route_instructions.as_slice() {
+            [(instruction, route_args)] => {
+                self.verify_transfer_authority(instruction, route_args)?;
+                self.verify_destination(instruction, route_args)?;
+                self.verify_route_args(route_args)
+            }
+            [] => Err(VerificationError::MissingRouteInstruction.into()),
+            _ => Err(VerificationError::MultipleRouteInstructions.into()),
+        }
+    }
+
+    fn verify_transfer_authority(
+        &self,
+        instruction: &VerifiedInstruction,
+        route_args: &RouteArgs,
+    ) -> Result<()> {
+        match route_account(instruction, route_args, "user_transfer_authority") {
+            Some(Ok(authority)) if authority == self.swap_request.user_public_key => Ok(()),
+            Some(Ok(authority)) => {
+                Err(VerificationError::UnexpectedTransferAuthority(authority).into())
+            }
+            Some(Err(index)) => Err(VerificationError::UnresolvedAccount(index).into()),
+            None => Err(VerificationError::MissingAccount("user_transfer_authority").into()),
+        }
+    }
+
+    /// The output must reach the user's associated token account of the output mint, or the
+    /// requested `destination_token_account`. `route` passes the Jupiter program as its
+    /// `destination_token_account` when there is none
+    fn verify_destination(
+        &self,
+        instruction: &VerifiedInstruction,
+        route_args: &RouteArgs,
+    ) -> Result<()> {
+        let user = &self.swap_request.user_public_key;
+        let output_mint = &self.swap_request.quote_response.output_mint;
+        let allowed = [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID]
+            .iter()
+            .map(|token_program_id| associated_token_address(user, output_mint, token_program_id))
+            .chain(self.swap_request.config.destination_token_account)
+            .collect::<Vec<_>>();
+        let names = route_args.kind.account_names();
+        for name in [
+            "user_destination_token_account",
+            "destination_token_account",
+        ] {
+            if !names.contains(&name) {
+                continue;
+            }
+            match route_account(instruction, route_args, name) {
+                Some(Ok(destination)) if allowed.contains(&destination) => {}
+                Some(Ok(JUPITER_PROGRAM_ID))
+                    if name == "destination_token_account"
+                        && names.contains(&"user_destination_token_account") => {}
+                Some(Ok(destination)) => {
+                    return Err(VerificationError::UnexpectedDestination(destination).into())
+                }
+                Some(Err(index)) => return Err(VerificationError::UnresolvedAccount(index).into()),
+                None => return Err(VerificationError::MissingAccount(name).into()),
+            }
+        }
+        Ok(())
+    }
+
+    fn verify_route_args(&self, route_args: &RouteArgs) -> Result<()> {
+        let quote_response = &self.swap_request.quote_response;
+        let platform_fee_bps = quote_response
+            .platform_fee
+            .as_ref()
+            .map_or(0, |platform_fee| platform_fee.fee_bps);
+        if route_args.platform_fee_bps != platform_fee_bps {
+            return Err(VerificationError::PlatformFeeMismatch {
+                expected: platform_fee_bps,
+                found: route_args.platform_fee_bps,
+            }
+            .into());
+        }
+        match route_args.amounts {
+            RouteAmounts::ExactIn {
+                in_amount,
+                quoted_out_amount,
+            } => {
+                if let Some(in_amount) = in_amount {
+                    check_amount("in_amount", quote_response.in_amount, in_amount)?;
+                }
+                // An overflow can only come from a slippage over 100%, which accepts any output
+                let minimum_out = amount_after_slippage(quoted_out_amount, route_args.slippage_bps)
+                    .unwrap_or_default();
+                if minimum_out < quote_response.other_amount_threshold {
+                    return Err(VerificationError::SlippageBoundExceeded {
+                        expected: quote_response.other_amount_threshold,
+                        found: minimum_out,
+                    }
+                    .into());
+                }
+            }
+            RouteAmounts::ExactOut {
+                out_amount,
+                quoted_in_amount,
+            } => {
+                check_amount("out_amount", quote_response.out_amount, out_amount)?;
+                let maximum_in = amount_with_slippage(quoted_in_amount, route_args.slippage_bps)
+                    .unwrap_or(u64::MAX);
+                if maximum_in > quote_response.other_amount_threshold {
+                    return Err(VerificationError::SlippageBoundExceeded {
+                        expected: quote_response.other_amount_threshold,
+                        found: maximum_in,
+                    }
+                    .into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Closing an account to the user, e.g. unwrapping SOL, and syncing the user's wrapped SOL account
+    fn verify_token_instruction(&self, instruction: &VerifiedInstruction) -> Result<()> {
+        let (position, expected) = match instruction.data {
+            [CLOSE_ACCOUNT_TAG] => (1, self.swap_request.user_public_key),
+            [SYNC_NATIVE_TAG] => (0, self.wrapped_sol_account),
+            _ => {
+                return Err(
+                    VerificationError::UnexpectedTokenInstruction(instruction.program_id).into(),
+                )
+            }
+        };
+        match instruction.accounts.get(position) {
+            Some(Ok(account)) if *account == expected => Ok(()),
+            Some(Ok(destination)) if position == 1 => {
+                Err(VerificationError::UnexpectedCloseDestination(*destination).into())
+            }
+            Some(Err(index)) => Err(VerificationError::UnresolvedAccount(*index).into()),
+            _ => Err(VerificationError::UnexpectedTokenInstruction(instruction.program_id).into()),
+        }
+    }
+
+    fn verify_system_instruction(&self, instruction: &VerifiedInstruction) -> Result<()> {
+        let Ok(SystemInstruction::Transfer { lamports }) =
+            bincode::deserialize::<SystemInstruction>(instruction.data)
+        else {
+            return Err(VerificationError::UnexpectedSystemInstruction.into());
+        };
+        let destination = match instruction.accounts.get(1) {
+            Some(Ok(destination)) => *destination,
+            Some(Err(index)) => return Err(VerificationError::UnresolvedAccount(*index).into()),
+            None => return Err(VerificationError::UnexpectedSystemInstruction.into()),
+        };
+        if !self.allowed_transfer_destinations.contains(&destination) {
+            return Err(VerificationError::UnexpectedTransfer {
+                destination,
+                lamports,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Static keys followed by the writable then the readonly keys loaded from lookup tables
+    fn account_keys(&self, message: &VersionedMessage) -> Vec<Option<Pubkey>> {
+        let mut account_keys = message
+            .static_account_keys()
+            .iter()
+            .copied()
+            .map(Some)
+            .collect::<Vec<_>>();
+        if let VersionedMessage::V0(message) = message {
+            let lookup = |table: &Pubkey, index: &u8| {
+                self.address_lookup_table_accounts
+                    .iter()
+                    .find(|account| &account.key == table)
+                    .and_then(|account| account.addresses.get(*index as usize))
+                    .copied()
+            };
+            for writable in [true, false] {
+                for table_lookup in &message.address_table_lookups {
+                    let indexes = if writable {
+                        &table_lookup.writable_indexes
+                    } else {
+                        &table_lookup.readonly_indexes
+                    };
+                    account_keys.extend(
+                        indexes
+                            .iter()
+                            .map(|index| lookup(&table_lookup.account_key, index)),
+                    );
+                }
+            }
+        }
+        account_keys
+    }
+}
+
+/// Account of the route instruction named `name`, `Err` with its index if it was not resolved
+fn route_account(
+    instruction: &VerifiedInstruction,
+    route_args: &RouteArgs,
+    name: &str,
+) -> Option<std::result::Result<Pubkey, usize>> {
+    let position = route_args
+        .kind
+        .account_names()
+        .iter()
+        .position(|account_name| *account_name == name)?;
+    instruction.accounts.get(position).copied()
+}
+
+fn check_amount(field: &'static str, expected: u64, found: u64) -> Result<()> {
+    if expected != found {
+        return Err(VerificationError::AmountMismatch {
+            field,
+            expected,
+            found,
+        }
+        .into());
+    }
+    Ok(())
+}
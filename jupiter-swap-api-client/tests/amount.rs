@@ -0,0 +1,93 @@
+use jupiter_swap_api_client::{
+    amount::{amount_after_slippage, amount_with_slippage, base_amount, ui_amount},
+    error::JupiterError,
+    quote::{QuoteResponse, SwapMode},
+    Decimal,
+};
+use solana_sdk::{pubkey, pubkey::Pubkey};
+
+const USDC_MINT: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+const NATIVE_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+
+fn quote_response(swap_mode: SwapMode) -> QuoteResponse {
+    serde_json::from_value(serde_json::json!({
+        "inputMint": USDC_MINT.to_string(),
+        "inAmount": "1000000",
+        "outputMint": NATIVE_MINT.to_string(),
+        "outAmount": "6000000",
+        "otherAmountThreshold": "5970000",
+        "swapMode": swap_mode,
+        "slippageBps": 50,
+        "priceImpactPct": "0.000012345678901234",
+        "routePlan": [],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn slippage_is_exact_for_large_amounts() {
+    assert_eq!(amount_after_slippage(u64::MAX, 0), Some(u64::MAX));
+    assert_eq!(
+        amount_after_slippage(u64::MAX, 50),
+        Some(18_354_510_353_341_003_856)
+    );
+    assert_eq!(amount_with_slippage(u64::MAX, 1), None);
+    assert_eq!(amount_with_slippage(1_000_001, 50), Some(1_005_002));
+    assert_eq!(amount_after_slippage(1, 10_001), None);
+}
+
+#[test]
+fn quote_amounts_follow_swap_mode() {
+    let exact_in = quote_response(SwapMode::ExactIn);
+    assert_eq!(exact_in.minimum_received(), Some(5_970_000));
+    assert_eq!(exact_in.maximum_sent(), Some(1_000_000));
+
+    let exact_out = quote_response(SwapMode::ExactOut);
+    assert_eq!(exact_out.minimum_received(), Some(6_000_000));
+    assert_eq!(exact_out.maximum_sent(), Some(1_005_000));
+}
+
+#[test]
+fn price_impact_and_effective_price_are_decimal() {
+    let quote_response = quote_response(SwapMode::ExactIn);
+
+    assert_eq!(
+        quote_response.price_impact().unwrap().to_string(),
+        "0.000012345678901234"
+    );
+    // 1 USDC for 0.006 SOL
+    assert_eq!(
+        quote_response.effective_price(6, 9),
+        Some(Decimal::new(6, 3))
+    );
+    assert_eq!(
+        ui_amount(u64::MAX, 9).unwrap().to_string(),
+        "18446744073.709551615"
+    );
+}
+
+#[test]
+fn base_amount_is_exact() {
+    // 2.01 * 1e6 is 2009999.9999999998 as a f64
+    assert_eq!(base_amount("2.01".parse().unwrap(), 6), Some(2_010_000));
+    assert_eq!(base_amount("0.29".parse().unwrap(), 6), Some(290_000));
+    assert_eq!(base_amount("0.0000019".parse().unwrap(), 6), Some(1));
+    assert_eq!(
+        base_amount("18446744073.709551615".parse().unwrap(), 9),
+        Some(u64::MAX)
+    );
+    assert_eq!(
+        base_amount("18446744073.709551616".parse().unwrap(), 9),
+        None
+    );
+    assert_eq!(base_amount("-1".parse().unwrap(), 6), None);
+}
+
+#[test]
+fn swap_mode_from_str() {
+    assert_eq!("ExactOut".parse::<SwapMode>().unwrap(), SwapMode::ExactOut);
+    assert!(matches!(
+        "exactIn".parse::<SwapMode>(),
+        Err(JupiterError::InvalidSwapMode(mode)) if mode == "exactIn"
+    ));
+}
@@ -0,0 +1,116 @@
+use jupiter_swap_api_client::{
+    assembler::SwapTransactionAssembler, error::JupiterError, swap::SwapInstructionsResponse,
+    transaction::SwapTransactionError,
+};
+use solana_sdk::{
+    address_lookup_table_account::AddressLookupTableAccount,
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    message::VersionedMessage,
+    packet::PACKET_DATA_SIZE,
+    pubkey::Pubkey,
+};
+
+/// Instruction tagged with `tag` so that its position can be found in the compiled message
+fn instruction(program_id: Pubkey, tag: u8, accounts: &[Pubkey]) -> Instruction {
+    Instruction::new_with_bytes(
+        program_id,
+        &[tag],
+        accounts
+            .iter()
+            .map(|account| AccountMeta::new(*account, false))
+            .collect(),
+    )
+}
+
+fn swap_instructions(program_id: Pubkey, accounts: &[Pubkey]) -> SwapInstructionsResponse {
+    SwapInstructionsResponse {
+        token_ledger_instruction: Some(instruction(program_id, 3, &[])),
+        compute_budget_instructions: vec![instruction(program_id, 0, &[])],
+        setup_instructions: vec![instruction(program_id, 2, &[])],
+        swap_instruction: instruction(program_id, 5, accounts),
+        cleanup_instruction: Some(instruction(program_id, 6, &[])),
+        address_lookup_table_addresses: vec![],
+    }
+}
+
+fn tags(message: &VersionedMessage) -> Vec<u8> {
+    message
+        .instructions()
+        .iter()
+        .map(|instruction| instruction.data[0])
+        .collect()
+}
+
+#[test]
+fn instructions_are_ordered() {
+    let payer = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
+    let swap_instructions = swap_instructions(program_id, &[]);
+
+    let transaction = SwapTransactionAssembler::new(&swap_instructions, payer, Hash::new_unique())
+        .after_cleanup(instruction(program_id, 7, &[]))
+        .before_swap(instruction(program_id, 4, &[]))
+        .before_setup(instruction(program_id, 1, &[]))
+        .build()
+        .unwrap();
+
+    assert_eq!(tags(&transaction.message), vec![0, 1, 2, 3, 4, 5, 6, 7]);
+    assert_eq!(transaction.message.static_account_keys()[0], payer);
+    assert_eq!(transaction.signatures.len(), 1);
+}
+
+#[test]
+fn lookup_tables_are_required_and_used() {
+    let payer = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
+    let accounts = (0..40).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+    let table = AddressLookupTableAccount {
+        key: Pubkey::new_unique(),
+        addresses: accounts.clone(),
+    };
+    let swap_instructions = SwapInstructionsResponse {
+        address_lookup_table_addresses: vec![table.key],
+        ..swap_instructions(program_id, &accounts)
+    };
+
+    let assembler = SwapTransactionAssembler::new(&swap_instructions, payer, Hash::new_unique());
+    assert!(matches!(
+        assembler.build(),
+        Err(JupiterError::Transaction(SwapTransactionError::MissingAddressLookupTable(key))) if key == table.key
+    ));
+
+    let transaction = assembler
+        .address_lookup_table_accounts(vec![table.clone()])
+        .build()
+        .unwrap();
+    let lookups = transaction.message.address_table_lookups().unwrap();
+    assert_eq!(lookups.len(), 1);
+    assert_eq!(lookups[0].account_key, table.key);
+    assert_eq!(lookups[0].writable_indexes.len(), accounts.len());
+    assert!(!transaction
+        .message
+        .static_account_keys()
+        .iter()
+        .any(|key| accounts.contains(key)));
+}
+
+#[test]
+fn oversized_transaction_is_rejected() {
+    let payer = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
+    // Without a lookup table 40 accounts take 1280 bytes of keys
+    let accounts = (0..40).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+    let swap_instructions = swap_instructions(program_id, &accounts);
+
+    match SwapTransactionAssembler::new(&swap_instructions, payer, Hash::new_unique()).build() {
+        Err(JupiterError::Transaction(SwapTransactionError::TransactionTooLarge {
+            size,
+            max_size,
+        })) => {
+            assert!(size > PACKET_DATA_SIZE);
+            assert_eq!(max_size, PACKET_DATA_SIZE);
+        }
+        result => panic!("unexpected result: {result:?}"),
+    }
+}
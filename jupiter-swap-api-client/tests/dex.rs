@@ -0,0 +1,80 @@
+use jupiter_swap_api_client::{
+    dex::{Dex, DexCatalog},
+    route_plan_with_metadata::SwapInfo,
+    rpc::InMemorySolanaRpc,
+};
+use solana_sdk::{account::Account, pubkey, pubkey::Pubkey};
+
+const USDC_MINT: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+const NATIVE_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+
+fn swap_info(amm_key: Pubkey, label: &str) -> SwapInfo {
+    SwapInfo {
+        amm_key,
+        label: label.into(),
+        input_mint: USDC_MINT,
+        output_mint: NATIVE_MINT,
+        in_amount: 1_000_000,
+        out_amount: 6_000_000,
+        fee_amount: 0,
+        fee_mint: USDC_MINT,
+    }
+}
+
+#[tokio::test]
+async fn swap_is_resolved_from_the_amm_owner_before_the_label() {
+    let dlmm = Pubkey::new_unique();
+    let pools = Pubkey::new_unique();
+    let mut dex_catalog = DexCatalog::new(vec![
+        Dex {
+            program_id: dlmm,
+            label: "Meteora".into(),
+        },
+        Dex {
+            program_id: pools,
+            label: "Meteora".into(),
+        },
+    ]);
+    let dlmm_amm = Pubkey::new_unique();
+    let unknown_amm = Pubkey::new_unique();
+    let rpc = InMemorySolanaRpc::new();
+    rpc.set_account(
+        dlmm_amm,
+        Account {
+            owner: dlmm,
+            ..Account::default()
+        },
+    );
+    rpc.set_account(
+        unknown_amm,
+        Account {
+            owner: Pubkey::new_unique(),
+            ..Account::default()
+        },
+    );
+
+    // Only the label is known before loading the owners, and it is ambiguous
+    let dlmm_swap = swap_info(dlmm_amm, "Meteora");
+    assert_eq!(
+        dex_catalog.for_swap(&dlmm_swap).map(|dex| dex.program_id),
+        Some(pools)
+    );
+
+    let unknown_swap = swap_info(unknown_amm, "Meteora");
+    dex_catalog
+        .load_amm_owners(&rpc, [&dlmm_swap, &unknown_swap])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        dex_catalog.for_swap(&dlmm_swap).map(|dex| dex.program_id),
+        Some(dlmm)
+    );
+    // An AMM owned by a program unknown to the API falls back to the label
+    assert_eq!(
+        dex_catalog
+            .for_swap(&unknown_swap)
+            .map(|dex| dex.program_id),
+        Some(pools)
+    );
+}
@@ -0,0 +1,45 @@
+use std::collections::HashSet;
+
+use jupiter_swap_api_client::endpoint::Endpoint;
+
+/// Fails to compile when a variant is added, as a reminder to check it is in `Endpoint::ALL`
+fn index(endpoint: Endpoint) -> usize {
+    match endpoint {
+        Endpoint::Quote => 0,
+        Endpoint::Swap => 1,
+        Endpoint::SwapInstructions => 2,
+        Endpoint::ProgramIdToLabel => 3,
+        Endpoint::IndexedRouteMap => 4,
+        Endpoint::Price => 5,
+        Endpoint::Token => 6,
+        Endpoint::TaggedTokens => 7,
+        Endpoint::TradableTokens => 8,
+        Endpoint::CreateLimitOrder => 9,
+        Endpoint::CancelLimitOrders => 10,
+        Endpoint::OpenLimitOrders => 11,
+        Endpoint::LimitOrderHistory => 12,
+        Endpoint::CreateRecurringOrder => 13,
+        Endpoint::CancelRecurringOrder => 14,
+        Endpoint::DepositRecurringOrder => 15,
+        Endpoint::WithdrawRecurringOrder => 16,
+        Endpoint::RecurringOrders => 17,
+        Endpoint::RecurringExecute => 18,
+        Endpoint::UltraOrder => 19,
+        Endpoint::UltraExecute => 20,
+    }
+}
+
+#[test]
+fn all_covers_every_endpoint() {
+    let indexes = Endpoint::ALL
+        .iter()
+        .map(|endpoint| index(*endpoint))
+        .collect::<Vec<_>>();
+    assert_eq!(indexes, (0..21).collect::<Vec<_>>());
+
+    let paths = Endpoint::ALL
+        .iter()
+        .map(Endpoint::path)
+        .collect::<HashSet<_>>();
+    assert_eq!(paths.len(), Endpoint::ALL.len());
+}
@@ -0,0 +1,456 @@
+use std::time::Duration;
+
+use jupiter_swap_api_client::{
+    endpoint::Endpoint,
+    error::JupiterError,
+    executor::{ExecutionError, SwapExecutor, SwapExecutorConfig},
+    freshness::QuoteFreshness,
+    jupiter_instruction::{JupiterInstructionKind, JUPITER_PROGRAM_ID},
+    mock_server::{MockJupiterServer, MockResponse},
+    programs::{associated_token_address, TOKEN_PROGRAM_ID},
+    quote::{QuoteRequest, QuoteResponse},
+    rpc::{ConfirmedTransaction, InMemorySolanaRpc, SignatureStatus, TokenBalance},
+    swap::SwapResponse,
+    verifier::VerificationError,
+    JupiterSwapApiClient,
+};
+use solana_sdk::{
+    hash::Hash,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    message::{v0, v0::LoadedAddresses, VersionedMessage},
+    pubkey,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    system_instruction,
+    transaction::{TransactionError, VersionedTransaction},
+};
+
+const USDC_MINT: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+const BONK_MINT: Pubkey = pubkey!("DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263");
+const CONTEXT_SLOT: u64 = 250_000_000;
+
+fn quote_request() -> QuoteRequest {
+    QuoteRequest {
+        amount: 1_000_000,
+        input_mint: USDC_MINT,
+        output_mint: BONK_MINT,
+        slippage_bps: 50,
+        ..QuoteRequest::default()
+    }
+}
+
+fn quote_response(context_slot: u64) -> QuoteResponse {
+    serde_json::from_value(serde_json::json!({
+        "inputMint": USDC_MINT.to_string(),
+        "inAmount": "1000000",
+        "outputMint": BONK_MINT.to_string(),
+        "outAmount": "2000000",
+        "otherAmountThreshold": "1990000",
+        "swapMode": "ExactIn",
+        "slippageBps": 50,
+        "priceImpactPct": "0",
+        "routePlan": [],
+        "contextSlot": context_slot,
+        "timeTaken": 0.01,
+    }))
+    .unwrap()
+}
+
+/// `route` instruction matching `quote_response`, with an empty route plan
+fn route_instruction(user: &Pubkey) -> Instruction {
+    let mut data = JupiterInstructionKind::Route.discriminator().to_vec();
+    data.extend(0u32.to_le_bytes());
+    data.extend(1_000_000u64.to_le_bytes());
+    data.extend(2_000_000u64.to_le_bytes());
+    data.extend(50u16.to_le_bytes());
+    data.push(0);
+    let destination = associated_token_address(user, &BONK_MINT, &TOKEN_PROGRAM_ID);
+    Instruction::new_with_bytes(
+        JUPITER_PROGRAM_ID,
+        &data,
+        vec![
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new(
+                associated_token_address(user, &USDC_MINT, &TOKEN_PROGRAM_ID),
+                false,
+            ),
+            AccountMeta::new(destination, false),
+            AccountMeta::new(destination, false),
+            AccountMeta::new_readonly(BONK_MINT, false),
+            AccountMeta::new_readonly(JUPITER_PROGRAM_ID, false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(JUPITER_PROGRAM_ID, false),
+        ],
+    )
+}
+
+fn transaction(
+    user: &Keypair,
+    instructions: &[Instruction],
+    recent_blockhash: Hash,
+) -> VersionedTransaction {
+    let message =
+        v0::Message::try_compile(&user.pubkey(), instructions, &[], recent_blockhash).unwrap();
+    VersionedTransaction {
+        signatures: vec![Signature::default()],
+        message: VersionedMessage::V0(message),
+    }
+}
+
+/// Unsigned swap transaction, each blockhash gives a different signature
+fn swap_transaction(user: &Keypair, recent_blockhash: Hash) -> VersionedTransaction {
+    transaction(user, &[route_instruction(&user.pubkey())], recent_blockhash)
+}
+
+/// Serve `transaction` from `/swap` and return the signature the executor will send it with
+fn push_swap(
+    server: &MockJupiterServer,
+    user: &Keypair,
+    transaction: &VersionedTransaction,
+    last_valid_block_height: u64,
+) -> Signature {
+    server.push_response(
+        Endpoint::Swap,
+        MockResponse::json(&SwapResponse {
+            swap_transaction: bincode::serialize(transaction).unwrap(),
+            last_valid_block_height,
+        }),
+    );
+    user.sign_message(&transaction.message.serialize())
+}
+
+fn token_balance(account_index: u8, owner: Pubkey, mint: Pubkey, amount: u64) -> TokenBalance {
+    TokenBalance {
+        account_index,
+        mint,
+        owner: Some(owner),
+        amount,
+    }
+}
+
+fn confirmed_transaction(
+    user: &Keypair,
+    transaction: VersionedTransaction,
+    out_amount: u64,
+) -> ConfirmedTransaction {
+    let owner = user.pubkey();
+    ConfirmedTransaction {
+        slot: 42,
+        transaction,
+        loaded_addresses: LoadedAddresses::default(),
+        err: None,
+        fee: 5_000,
+        pre_balances: vec![],
+        post_balances: vec![],
+        pre_token_balances: vec![
+            token_balance(2, owner, USDC_MINT, 5_000_000),
+            token_balance(3, owner, BONK_MINT, 1_000_000),
+        ],
+        post_token_balances: vec![
+            token_balance(2, owner, USDC_MINT, 4_000_000),
+            token_balance(3, owner, BONK_MINT, out_amount),
+        ],
+        log_messages: vec![],
+        inner_instructions: vec![],
+    }
+}
+
+fn confirmed(slot: u64, err: Option<TransactionError>) -> SignatureStatus {
+    SignatureStatus {
+        slot,
+        err,
+        confirmed: true,
+    }
+}
+
+fn executor(client: JupiterSwapApiClient, max_rebuilds: u32) -> SwapExecutor<InMemorySolanaRpc> {
+    SwapExecutor::new(
+        client,
+        InMemorySolanaRpc::new(),
+        SwapExecutorConfig {
+            max_rebuilds,
+            confirmation_poll_interval: Duration::from_millis(5),
+            ..SwapExecutorConfig::default()
+        },
+    )
+}
+
+#[tokio::test]
+async fn processed_transaction_is_awaited_instead_of_resent() {
+    let user = Keypair::new();
+    let server = MockJupiterServer::start();
+    server.push_response(
+        Endpoint::Quote,
+        MockResponse::json(&quote_response(CONTEXT_SLOT)),
+    );
+    let transaction = swap_transaction(&user, Hash::new_unique());
+    let signature = push_swap(&server, &user, &transaction, 10);
+    let executor = executor(JupiterSwapApiClient::new(server.base_path()), 2);
+    let rpc = executor.rpc();
+    // Processed but not confirmed yet, after its blockhash expired
+    rpc.set_block_height(100);
+    rpc.set_signature_status(
+        signature,
+        SignatureStatus {
+            confirmed: false,
+            ..confirmed(42, None)
+        },
+    );
+    let mut signed = transaction.clone();
+    signed.signatures = vec![signature];
+    rpc.add_transaction(signature, confirmed_transaction(&user, signed, 2_995_000));
+
+    let quote_request = quote_request();
+    let signers = [&user];
+    let (execution, _) = tokio::join!(
+        executor.execute(&quote_request, user.pubkey(), &signers),
+        async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            rpc.set_signature_status(signature, confirmed(42, None));
+        }
+    );
+    let execution = execution.unwrap();
+
+    assert_eq!(execution.signature, signature);
+    assert_eq!(execution.slot, 42);
+    assert_eq!(execution.in_amount, 1_000_000);
+    assert_eq!(execution.out_amount, 1_995_000);
+    assert_eq!(rpc.sent_transactions().len(), 1);
+    assert_eq!(server.requests_to(Endpoint::Swap).len(), 1);
+}
+
+#[tokio::test]
+async fn expired_blockhash_is_rebuilt_up_to_max_rebuilds() {
+    let user = Keypair::new();
+    let server = MockJupiterServer::start();
+    for _ in 0..3 {
+        server.push_response(
+            Endpoint::Quote,
+            MockResponse::json(&quote_response(CONTEXT_SLOT)),
+        );
+    }
+    let expired = swap_transaction(&user, Hash::new_unique());
+    push_swap(&server, &user, &expired, 10);
+    let landed = swap_transaction(&user, Hash::new_unique());
+    let signature = push_swap(&server, &user, &landed, 1_000);
+    let executor = executor(JupiterSwapApiClient::new(server.base_path()), 1);
+    let rpc = executor.rpc();
+    rpc.set_block_height(100);
+    rpc.set_signature_status(signature, confirmed(43, None));
+    let mut signed = landed.clone();
+    signed.signatures = vec![signature];
+    rpc.add_transaction(signature, confirmed_transaction(&user, signed, 3_000_000));
+
+    let execution = executor
+        .execute(&quote_request(), user.pubkey(), &[&user])
+        .await
+        .unwrap();
+    assert_eq!(execution.signature, signature);
+    assert_eq!(rpc.sent_transactions().len(), 2);
+    assert_eq!(server.requests_to(Endpoint::Quote).len(), 2);
+
+    // Both builds expire, the second one is the last allowed
+    push_swap(
+        &server,
+        &user,
+        &swap_transaction(&user, Hash::new_unique()),
+        10,
+    );
+    let last = push_swap(
+        &server,
+        &user,
+        &swap_transaction(&user, Hash::new_unique()),
+        10,
+    );
+    server.push_response(
+        Endpoint::Quote,
+        MockResponse::json(&quote_response(CONTEXT_SLOT)),
+    );
+    match executor
+        .execute(&quote_request(), user.pubkey(), &[&user])
+        .await
+    {
+        Err(JupiterError::Execution(ExecutionError::BlockhashExpired(expired))) => {
+            assert_eq!(expired, last)
+        }
+        result => panic!("unexpected result: {result:?}"),
+    }
+    assert_eq!(rpc.sent_transactions().len(), 4);
+}
+
+#[tokio::test]
+async fn stale_quote_is_quoted_again() {
+    let user = Keypair::new();
+    let server = MockJupiterServer::start();
+    server.push_response(
+        Endpoint::Quote,
+        MockResponse::json(&quote_response(CONTEXT_SLOT + 20)),
+    );
+    server.push_response(
+        Endpoint::Quote,
+        MockResponse::json(&quote_response(CONTEXT_SLOT)),
+    );
+    server.push_response(
+        Endpoint::Quote,
+        MockResponse::json(&quote_response(CONTEXT_SLOT + 21)),
+    );
+    let client = JupiterSwapApiClient::builder(server.base_path())
+        .quote_freshness(QuoteFreshness {
+            max_slot_lag: Some(10),
+            max_age: None,
+        })
+        .build()
+        .unwrap();
+    // The client has seen a newer slot than the next quote
+    client.quote(&quote_request()).await.unwrap();
+    let transaction = swap_transaction(&user, Hash::new_unique());
+    let signature = push_swap(&server, &user, &transaction, 1_000);
+    let executor = executor(client, 1);
+    let rpc = executor.rpc();
+    rpc.set_signature_status(signature, confirmed(44, None));
+    let mut signed = transaction.clone();
+    signed.signatures = vec![signature];
+    rpc.add_transaction(signature, confirmed_transaction(&user, signed, 3_000_000));
+
+    let execution = executor
+        .execute(&quote_request(), user.pubkey(), &[&user])
+        .await
+        .unwrap();
+
+    assert_eq!(execution.quote_response.context_slot, CONTEXT_SLOT + 21);
+    assert_eq!(server.requests_to(Endpoint::Quote).len(), 3);
+    assert_eq!(server.requests_to(Endpoint::Swap).len(), 1);
+}
+
+#[tokio::test]
+async fn failed_transaction_and_unexpected_amounts_are_errors() {
+    let user = Keypair::new();
+    let server = MockJupiterServer::start();
+    for _ in 0..2 {
+        server.push_response(
+            Endpoint::Quote,
+            MockResponse::json(&quote_response(CONTEXT_SLOT)),
+        );
+    }
+    let failed = push_swap(
+        &server,
+        &user,
+        &swap_transaction(&user, Hash::new_unique()),
+        1_000,
+    );
+    let transaction = swap_transaction(&user, Hash::new_unique());
+    let signature = push_swap(&server, &user, &transaction, 1_000);
+    let executor = executor(JupiterSwapApiClient::new(server.base_path()), 2);
+    let rpc = executor.rpc();
+    let err = TransactionError::InstructionError(2, InstructionError::Custom(6001));
+    rpc.set_signature_status(failed, confirmed(45, Some(err.clone())));
+    rpc.set_signature_status(signature, confirmed(46, None));
+    let mut signed = transaction.clone();
+    signed.signatures = vec![signature];
+    // The output balance went down
+    rpc.add_transaction(signature, confirmed_transaction(&user, signed, 500_000));
+
+    match executor
+        .execute(&quote_request(), user.pubkey(), &[&user])
+        .await
+    {
+        Err(JupiterError::Execution(ExecutionError::TransactionFailed {
+            signature,
+            err: failed_err,
+        })) => {
+            assert_eq!(signature, failed);
+            assert_eq!(failed_err, err);
+        }
+        result => panic!("unexpected result: {result:?}"),
+    }
+    match executor
+        .execute(&quote_request(), user.pubkey(), &[&user])
+        .await
+    {
+        Err(JupiterError::Execution(ExecutionError::UnexpectedBalanceChange { mint, change })) => {
+            assert_eq!(mint, BONK_MINT);
+            assert_eq!(change, -500_000);
+        }
+        result => panic!("unexpected result: {result:?}"),
+    }
+}
+
+#[tokio::test]
+async fn unexpected_transaction_is_not_signed() {
+    let user = Keypair::new();
+    let attacker = Pubkey::new_unique();
+    let server = MockJupiterServer::start();
+    server.push_response(
+        Endpoint::Quote,
+        MockResponse::json(&quote_response(CONTEXT_SLOT)),
+    );
+    let transaction = transaction(
+        &user,
+        &[
+            route_instruction(&user.pubkey()),
+            system_instruction::transfer(&user.pubkey(), &attacker, 1_000_000_000),
+        ],
+        Hash::new_unique(),
+    );
+    push_swap(&server, &user, &transaction, 1_000);
+    let executor = executor(JupiterSwapApiClient::new(server.base_path()), 2);
+
+    match executor
+        .execute(&quote_request(), user.pubkey(), &[&user])
+        .await
+    {
+        Err(JupiterError::Verification(VerificationError::UnexpectedTransfer {
+            destination,
+            ..
+        })) => assert_eq!(destination, attacker),
+        result => panic!("unexpected result: {result:?}"),
+    }
+    assert!(executor.rpc().sent_transactions().is_empty());
+}
+
+#[tokio::test]
+async fn unconfirmed_transaction_times_out() {
+    let user = Keypair::new();
+    let server = MockJupiterServer::start();
+    server.push_response(
+        Endpoint::Quote,
+        MockResponse::json(&quote_response(CONTEXT_SLOT)),
+    );
+    let signature = push_swap(
+        &server,
+        &user,
+        &swap_transaction(&user, Hash::new_unique()),
+        10,
+    );
+    let executor = SwapExecutor::new(
+        JupiterSwapApiClient::new(server.base_path()),
+        InMemorySolanaRpc::new(),
+        SwapExecutorConfig {
+            confirmation_poll_interval: Duration::from_millis(5),
+            confirmation_timeout: Duration::from_millis(50),
+            ..SwapExecutorConfig::default()
+        },
+    );
+    let rpc = executor.rpc();
+    // Processed but never confirmed
+    rpc.set_signature_status(
+        signature,
+        SignatureStatus {
+            confirmed: false,
+            ..confirmed(42, None)
+        },
+    );
+
+    match executor
+        .execute(&quote_request(), user.pubkey(), &[&user])
+        .await
+    {
+        Err(JupiterError::Execution(ExecutionError::ConfirmationTimeout(timed_out))) => {
+            assert_eq!(timed_out, signature)
+        }
+        result => panic!("unexpected result: {result:?}"),
+    }
+    assert_eq!(rpc.sent_transactions().len(), 1);
+}
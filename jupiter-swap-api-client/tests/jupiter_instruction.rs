@@ -0,0 +1,125 @@
+use jupiter_swap_api_client::{
+    error::JupiterError,
+    jupiter_instruction::{
+        JupiterInstruction, JupiterInstructionKind, RouteAmounts, RouteInstructionData, RouteStep,
+        Side, Swap, JUPITER_PROGRAM_ID,
+    },
+};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+/// `shared_accounts_route` splitting 60/40 between Whirlpool and Phoenix, then through Meteora DLMM
+fn shared_accounts_route_data() -> Vec<u8> {
+    let mut data = JupiterInstructionKind::SharedAccountsRoute
+        .discriminator()
+        .to_vec();
+    data.push(3);
+    data.extend(3u32.to_le_bytes());
+    data.extend([17, 1, 60, 0, 1]);
+    data.extend([28, 1, 40, 0, 1]);
+    data.extend([38, 100, 1, 2]);
+    data.extend(1_000_000u64.to_le_bytes());
+    data.extend(6_000_000u64.to_le_bytes());
+    data.extend(50u16.to_le_bytes());
+    data.push(20);
+    data
+}
+
+#[test]
+fn decode_shared_accounts_route() {
+    let account_names = JupiterInstructionKind::SharedAccountsRoute.account_names();
+    let accounts = (0..account_names.len() + 2)
+        .map(|_| AccountMeta::new(Pubkey::new_unique(), false))
+        .collect::<Vec<_>>();
+    let instruction = Instruction {
+        program_id: JUPITER_PROGRAM_ID,
+        accounts: accounts.clone(),
+        data: shared_accounts_route_data(),
+    };
+
+    let decoded = JupiterInstruction::decode(&instruction).unwrap();
+    assert_eq!(decoded.data.id, Some(3));
+    assert_eq!(
+        decoded.data.route_plan,
+        Some(vec![
+            RouteStep {
+                swap: Swap::Whirlpool { a_to_b: true },
+                percent: 60,
+                input_index: 0,
+                output_index: 1,
+            },
+            RouteStep {
+                swap: Swap::Phoenix { side: Side::Ask },
+                percent: 40,
+                input_index: 0,
+                output_index: 1,
+            },
+            RouteStep {
+                swap: Swap::MeteoraDlmm,
+                percent: 100,
+                input_index: 1,
+                output_index: 2,
+            },
+        ])
+    );
+    assert_eq!(
+        decoded.data.args.amounts,
+        RouteAmounts::ExactIn {
+            in_amount: Some(1_000_000),
+            quoted_out_amount: 6_000_000,
+        }
+    );
+    assert_eq!(decoded.data.args.slippage_bps, 50);
+    assert_eq!(decoded.data.args.platform_fee_bps, 20);
+    assert_eq!(
+        decoded.account("user_transfer_authority"),
+        Some(&accounts[2])
+    );
+    assert_eq!(decoded.remaining_accounts, accounts[account_names.len()..]);
+
+    let missing_accounts = Instruction {
+        accounts: accounts[..4].to_vec(),
+        ..instruction
+    };
+    assert!(matches!(
+        JupiterInstruction::decode(&missing_accounts),
+        Err(JupiterError::InvalidJupiterInstruction(_))
+    ));
+}
+
+#[test]
+fn unknown_amm_keeps_route_args() {
+    let mut data = shared_accounts_route_data();
+    // Replace Meteora DLMM with an AMM unknown to the decoder
+    data[23] = 200;
+
+    let decoded = RouteInstructionData::decode(&data).unwrap();
+    assert_eq!(decoded.route_plan, None);
+    assert_eq!(decoded.args.slippage_bps, 50);
+}
+
+#[test]
+fn patch_in_amount() {
+    let mut data = shared_accounts_route_data();
+    let mut args = RouteInstructionData::decode(&data).unwrap().args;
+    args.amounts = RouteAmounts::ExactIn {
+        in_amount: Some(2_000_000),
+        quoted_out_amount: 12_000_000,
+    };
+    args.patch(&mut data).unwrap();
+
+    let decoded = RouteInstructionData::decode(&data).unwrap();
+    assert_eq!(decoded.args, args);
+    assert_eq!(
+        decoded.route_plan.map(|route_plan| route_plan.len()),
+        Some(3)
+    );
+
+    args.amounts = RouteAmounts::ExactOut {
+        out_amount: 1,
+        quoted_in_amount: 1,
+    };
+    assert!(args.patch(&mut data).is_err());
+}
@@ -0,0 +1,772 @@
+use std::time::{Duration, Instant};
+
+use jupiter_swap_api_client::{
+    cassette::{Cassette, CassetteError},
+    dex::{IndexedRouteMapRequest, RouteMap, UnknownDex},
+    endpoint::Endpoint,
+    error::{ErrorCode, JupiterError},
+    failover::{FailoverMode, FailoverPolicy},
+    freshness::{QuoteFreshness, StaleQuote},
+    limit_order::{CreateOrderParams, CreateOrderRequest},
+    mock_server::{MockJupiterServer, MockResponse},
+    price::{PriceRequest, PriceType},
+    quote::{QuoteRequest, QuoteResponse},
+    recurring::{
+        CreateRecurringOrderRequest, RecurringOrderParams, RecurringOrdersRequest,
+        TimeRecurringParams,
+    },
+    retry::RetryPolicy,
+    swap::{SwapRequest, SwapResponse},
+    token::TokenTag,
+    transaction::EncodedTransaction,
+    transaction_config::TransactionConfig,
+    ultra::{ExecuteRequest, ExecuteStatus, OrderRequest, UltraError},
+    Decimal, JupiterSwapApiClient,
+};
+use solana_sdk::{pubkey, pubkey::Pubkey};
+
+const USDC_MINT: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+const NATIVE_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+const TEST_WALLET: Pubkey = pubkey!("2AQdpHJ2JpcEgPiATUXjQxA8QmafFegfQwSLWSprPicm");
+
+fn quote_request() -> QuoteRequest {
+    QuoteRequest {
+        amount: 1_000_000,
+        input_mint: USDC_MINT,
+        output_mint: NATIVE_MINT,
+        slippage_bps: 50,
+        ..QuoteRequest::default()
+    }
+}
+
+fn quote_response() -> QuoteResponse {
+    serde_json::from_value(serde_json::json!({
+        "inputMint": USDC_MINT.to_string(),
+        "inAmount": "1000000",
+        "outputMint": NATIVE_MINT.to_string(),
+        "outAmount": "6000000",
+        "otherAmountThreshold": "5970000",
+        "swapMode": "ExactIn",
+        "slippageBps": 50,
+        "priceImpactPct": "0",
+        "routePlan": [],
+        "contextSlot": 250_000_000,
+        "timeTaken": 0.01,
+    }))
+    .unwrap()
+}
+
+fn retry_policy() -> RetryPolicy {
+    RetryPolicy {
+        initial_backoff: Duration::from_millis(1),
+        ..RetryPolicy::default()
+    }
+}
+
+#[tokio::test]
+async fn quote_sends_query_string() {
+    let server = MockJupiterServer::start();
+    server.push_response(Endpoint::Quote, MockResponse::json(&quote_response()));
+    let client = JupiterSwapApiClient::new(server.base_path());
+
+    let quote_response = client.quote(&quote_request()).await.unwrap();
+
+    assert_eq!(quote_response.out_amount, 6_000_000);
+    let requests = server.requests_to(Endpoint::Quote);
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "GET");
+    assert_eq!(
+        requests[0].query.as_deref(),
+        Some(
+            "inputMint=EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v\
+             &outputMint=So11111111111111111111111111111111111111112\
+             &amount=1000000&slippageBps=50"
+        )
+    );
+}
+
+#[tokio::test]
+async fn swap_posts_quote_response() {
+    let server = MockJupiterServer::start();
+    server.push_response(
+        Endpoint::Swap,
+        MockResponse::json(&SwapResponse {
+            swap_transaction: vec![1, 2, 3],
+            last_valid_block_height: 42,
+        }),
+    );
+    let client = JupiterSwapApiClient::new(server.base_path());
+
+    let swap_response = client
+        .swap(&SwapRequest {
+            user_public_key: TEST_WALLET,
+            quote_response: quote_response(),
+            config: TransactionConfig::default(),
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(swap_response.swap_transaction, vec![1, 2, 3]);
+    assert_eq!(swap_response.last_valid_block_height, 42);
+    let body: serde_json::Value =
+        serde_json::from_str(&server.requests_to(Endpoint::Swap)[0].body).unwrap();
+    assert_eq!(body["userPublicKey"], TEST_WALLET.to_string());
+    assert_eq!(body["quoteResponse"]["outAmount"], "6000000");
+}
+
+#[tokio::test]
+async fn error_code_is_parsed() {
+    let server = MockJupiterServer::start();
+    server.push_response(
+        Endpoint::Quote,
+        MockResponse::error_code(400, "COULD_NOT_FIND_ANY_ROUTE"),
+    );
+    let client = JupiterSwapApiClient::new(server.base_path());
+
+    let error = client.quote(&quote_request()).await.unwrap_err();
+
+    assert!(error.is_no_route());
+    assert_eq!(error.error_code(), Some(&ErrorCode::CouldNotFindAnyRoute));
+}
+
+#[tokio::test]
+async fn malformed_json_keeps_body() {
+    let server = MockJupiterServer::start();
+    server.push_response(Endpoint::Quote, MockResponse::Malformed("{not json".into()));
+    let client = JupiterSwapApiClient::new(server.base_path());
+
+    match client.quote(&quote_request()).await.unwrap_err() {
+        JupiterError::Deserialize { body, .. } => assert_eq!(body, "{not json"),
+        error => panic!("unexpected error: {error}"),
+    }
+}
+
+#[tokio::test]
+async fn quote_is_retried_on_rate_limit_and_server_error() {
+    let server = MockJupiterServer::start();
+    server.push_response(Endpoint::Quote, MockResponse::rate_limited(Some(0)));
+    server.push_response(Endpoint::Quote, MockResponse::status(500, "oops"));
+    server.push_response(Endpoint::Quote, MockResponse::json(&quote_response()));
+    let client = JupiterSwapApiClient::builder(server.base_path())
+        .retry_policy(retry_policy())
+        .build()
+        .unwrap();
+
+    client.quote(&quote_request()).await.unwrap();
+
+    assert_eq!(server.requests_to(Endpoint::Quote).len(), 3);
+}
+
+#[tokio::test]
+async fn swap_is_not_retried_by_default() {
+    let server = MockJupiterServer::start();
+    server.push_response(Endpoint::Swap, MockResponse::status(503, "unavailable"));
+    let client = JupiterSwapApiClient::builder(server.base_path())
+        .retry_policy(retry_policy())
+        .build()
+        .unwrap();
+
+    let error = client
+        .swap(&SwapRequest {
+            user_public_key: TEST_WALLET,
+            quote_response: quote_response(),
+            config: TransactionConfig::default(),
+        })
+        .await
+        .unwrap_err();
+
+    assert!(error.is_server_error());
+    assert_eq!(server.requests_to(Endpoint::Swap).len(), 1);
+}
+
+#[tokio::test]
+async fn ultra_execute_is_never_retried() {
+    let server = MockJupiterServer::start();
+    server.push_response(
+        Endpoint::UltraExecute,
+        MockResponse::status(503, "unavailable"),
+    );
+    let client = JupiterSwapApiClient::builder(server.base_path())
+        .api_base_path(server.base_path())
+        .retry_policy(RetryPolicy {
+            retry_swap: true,
+            ..retry_policy()
+        })
+        .build()
+        .unwrap();
+
+    let error = client
+        .ultra_execute(&ExecuteRequest {
+            signed_transaction: EncodedTransaction(vec![1, 2, 3]),
+            request_id: "request-1".into(),
+        })
+        .await
+        .unwrap_err();
+
+    assert!(error.is_server_error());
+    assert_eq!(server.requests_to(Endpoint::UltraExecute).len(), 1);
+}
+
+#[tokio::test]
+async fn slow_response_times_out() {
+    let server = MockJupiterServer::start();
+    server.push_response(
+        Endpoint::Quote,
+        MockResponse::delayed(
+            Duration::from_millis(500),
+            MockResponse::json(&quote_response()),
+        ),
+    );
+    let client = JupiterSwapApiClient::builder(server.base_path())
+        .timeout(Duration::from_millis(50))
+        .build()
+        .unwrap();
+
+    match client.quote(&quote_request()).await.unwrap_err() {
+        JupiterError::Transport(error) => assert!(error.is_timeout()),
+        error => panic!("unexpected error: {error}"),
+    }
+}
+
+#[tokio::test]
+async fn failover_moves_to_next_base_path() {
+    let primary = MockJupiterServer::start();
+    let fallback = MockJupiterServer::start();
+    primary.push_response(Endpoint::Quote, MockResponse::status(502, "bad gateway"));
+    fallback.push_response(Endpoint::Quote, MockResponse::json(&quote_response()));
+    let client = JupiterSwapApiClient::builder(primary.base_path())
+        .fallback_base_path(fallback.base_path())
+        .failover_policy(FailoverPolicy::default())
+        .build()
+        .unwrap();
+
+    client.quote(&quote_request()).await.unwrap();
+    // The primary is cooling down, so the fallback is used first
+    fallback.push_response(Endpoint::Quote, MockResponse::json(&quote_response()));
+    client.quote(&quote_request()).await.unwrap();
+
+    assert_eq!(primary.requests().len(), 1);
+    assert_eq!(fallback.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn hedged_quote_uses_fastest_base_path() {
+    let primary = MockJupiterServer::start();
+    let hedge = MockJupiterServer::start();
+    let mut slow_quote = quote_response();
+    slow_quote.out_amount = 1;
+    primary.push_response(
+        Endpoint::Quote,
+        MockResponse::delayed(Duration::from_secs(5), MockResponse::json(&slow_quote)),
+    );
+    hedge.push_response(Endpoint::Quote, MockResponse::json(&quote_response()));
+    let client = JupiterSwapApiClient::builder(primary.base_path())
+        .fallback_base_path(hedge.base_path())
+        .failover_policy(FailoverPolicy {
+            mode: FailoverMode::Hedged {
+                latency_budget: Duration::from_millis(50),
+            },
+            ..FailoverPolicy::default()
+        })
+        .build()
+        .unwrap();
+
+    let start = Instant::now();
+    let quote_response = client.quote(&quote_request()).await.unwrap();
+
+    // The hedge answered first and the slow primary response was not waited for
+    assert_eq!(quote_response.out_amount, 6_000_000);
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(primary.requests().len(), 1);
+    assert_eq!(hedge.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn cassette_replays_recorded_session() {
+    let path = std::env::temp_dir().join(format!("jupiter-cassette-{}.json", std::process::id()));
+    let server = MockJupiterServer::start();
+    server.push_response(Endpoint::Quote, MockResponse::json(&quote_response()));
+    server.push_response(
+        Endpoint::Swap,
+        MockResponse::json(&SwapResponse {
+            swap_transaction: vec![1, 2, 3],
+            last_valid_block_height: 42,
+        }),
+    );
+    let swap_request = SwapRequest {
+        user_public_key: TEST_WALLET,
+        quote_response: quote_response(),
+        config: TransactionConfig::default(),
+    };
+    let client = JupiterSwapApiClient::builder(server.base_path())
+        .cassette(Cassette::record(&path))
+        .build()
+        .unwrap();
+    client.quote(&quote_request()).await.unwrap();
+    client.swap(&swap_request).await.unwrap();
+    client.save_cassette().await.unwrap();
+    drop(server);
+
+    // Nothing listens on the base path anymore
+    let client = JupiterSwapApiClient::builder("http://127.0.0.1:9".into())
+        .cassette(Cassette::replay(&path).unwrap())
+        .build()
+        .unwrap();
+    let replayed_quote = client.quote(&quote_request()).await.unwrap();
+    let replayed_swap = client.swap(&swap_request).await.unwrap();
+    let miss = client.quote(&quote_request()).await.unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(replayed_quote.out_amount, 6_000_000);
+    assert_eq!(replayed_swap.swap_transaction, vec![1, 2, 3]);
+    assert!(matches!(
+        miss,
+        JupiterError::Cassette(CassetteError::Miss { .. })
+    ));
+}
+
+#[tokio::test]
+async fn price_sends_ids_and_parses_missing_prices() {
+    let server = MockJupiterServer::start();
+    server.push_response(
+        Endpoint::Price,
+        MockResponse::Json(serde_json::json!({
+            "data": {
+                NATIVE_MINT.to_string(): {
+                    "id": NATIVE_MINT.to_string(),
+                    "type": "derivedPrice",
+                    "price": "133.17",
+                },
+                TEST_WALLET.to_string(): null,
+            },
+            "timeTaken": 0.003,
+        })),
+    );
+    let client = JupiterSwapApiClient::builder(server.base_path())
+        .api_base_path(server.base_path())
+        .build()
+        .unwrap();
+
+    let price_response = client
+        .price(&PriceRequest {
+            vs_token: Some(USDC_MINT),
+            ..PriceRequest::new(vec![NATIVE_MINT, TEST_WALLET])
+        })
+        .await
+        .unwrap();
+
+    let price = price_response.get(&NATIVE_MINT).unwrap();
+    assert_eq!(price.price, 133.17);
+    assert_eq!(price.price_type, PriceType::DerivedPrice);
+    assert!(price_response.get(&TEST_WALLET).is_none());
+    assert_eq!(
+        server.requests_to(Endpoint::Price)[0].query.as_deref(),
+        Some(
+            "ids=So11111111111111111111111111111111111111112%2C2AQdpHJ2JpcEgPiATUXjQxA8QmafFegfQwSLWSprPicm\
+             &vsToken=EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+        )
+    );
+}
+
+#[tokio::test]
+async fn token_lookup_uses_path_parameter() {
+    let server = MockJupiterServer::start();
+    server.push_response_for_path(
+        &format!("/tokens/v1/token/{USDC_MINT}"),
+        MockResponse::Json(serde_json::json!({
+            "address": USDC_MINT.to_string(),
+            "name": "USD Coin",
+            "symbol": "USDC",
+            "decimals": 6,
+            "logoURI": "https://example.com/usdc.png",
+            "tags": ["verified", "strict", "new-tag"],
+            "daily_volume": 1.0e9,
+            "created_at": "2024-04-26T10:56:58.893768Z",
+            "freeze_authority": "7dGbd2QZcCKcTndnHcTL8q7SMVXAkp688NTQYwrRCrar",
+            "mint_authority": null,
+            "permanent_delegate": null,
+            "minted_at": null,
+            "extensions": { "coingeckoId": "usd-coin" },
+        })),
+    );
+    let client = JupiterSwapApiClient::builder(server.base_path())
+        .api_base_path(server.base_path())
+        .build()
+        .unwrap();
+
+    let token = client.token(&USDC_MINT).await.unwrap().unwrap();
+
+    assert_eq!(token.decimals, 6);
+    assert_eq!(
+        token.ui_amount(quote_response().in_amount),
+        Some(Decimal::ONE)
+    );
+    assert_eq!(token.amount("2.01".parse().unwrap()), Some(2_010_000));
+    assert_eq!(token.amount("0.29".parse().unwrap()), Some(290_000));
+    assert!(token.has_tag(&TokenTag::Strict));
+    assert!(token.has_tag(&TokenTag::Other("new-tag".into())));
+    assert!(token.has_freeze_authority());
+    assert!(!token.has_mint_authority());
+}
+
+#[tokio::test]
+async fn dex_catalog_rejects_unknown_labels_before_quoting() {
+    let server = MockJupiterServer::start();
+    server.push_response(
+        Endpoint::ProgramIdToLabel,
+        MockResponse::Json(serde_json::json!({
+            "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc": "Whirlpool",
+            "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8": "Raydium",
+        })),
+    );
+    let dex_catalog = JupiterSwapApiClient::new(server.base_path())
+        .dex_catalog()
+        .await
+        .unwrap();
+    let client = JupiterSwapApiClient::builder(server.base_path())
+        .dex_catalog(dex_catalog)
+        .build()
+        .unwrap();
+
+    let error = client
+        .quote(&QuoteRequest {
+            excluded_dexes: Some(vec!["Whirlpool".into(), "Raydim".into()]),
+            ..quote_request()
+        })
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, JupiterError::UnknownDex(UnknownDex(label)) if label == "Raydim"));
+    assert!(server.requests_to(Endpoint::Quote).is_empty());
+}
+
+#[tokio::test]
+async fn route_map_answers_reachability_offline() {
+    let server = MockJupiterServer::start();
+    server.push_response(
+        Endpoint::IndexedRouteMap,
+        MockResponse::Json(serde_json::json!({
+            "mintKeys": [USDC_MINT.to_string(), NATIVE_MINT.to_string(), TEST_WALLET.to_string()],
+            "indexedRouteMap": { "0": [1], "1": [0, 2] },
+        })),
+    );
+    let client = JupiterSwapApiClient::new(server.base_path());
+
+    let indexed_route_map = client
+        .indexed_route_map(&IndexedRouteMapRequest::default())
+        .await
+        .unwrap();
+    let route_map = RouteMap::from(&indexed_route_map);
+
+    assert!(route_map.has_route(&USDC_MINT, &NATIVE_MINT));
+    assert!(!route_map.has_route(&USDC_MINT, &TEST_WALLET));
+    assert!(route_map.reachable(&USDC_MINT, 2).contains(&TEST_WALLET));
+    assert!(!route_map.reachable(&USDC_MINT, 1).contains(&TEST_WALLET));
+}
+
+#[tokio::test]
+async fn create_limit_order_posts_amounts_as_strings() {
+    let server = MockJupiterServer::start();
+    server.push_response(
+        Endpoint::CreateLimitOrder,
+        MockResponse::Json(serde_json::json!({
+            "order": USDC_MINT.to_string(),
+            "tx": "AQID",
+        })),
+    );
+    let client = JupiterSwapApiClient::builder(server.base_path())
+        .api_base_path(server.base_path())
+        .build()
+        .unwrap();
+
+    let create_order_response = client
+        .create_limit_order(&CreateOrderRequest {
+            input_mint: USDC_MINT,
+            output_mint: NATIVE_MINT,
+            maker: TEST_WALLET,
+            payer: TEST_WALLET,
+            params: CreateOrderParams {
+                making_amount: 1_000_000,
+                taking_amount: 7_000_000,
+                ..CreateOrderParams::default()
+            },
+            ..CreateOrderRequest::default()
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(create_order_response.tx.0, vec![1, 2, 3]);
+    let body: serde_json::Value =
+        serde_json::from_str(&server.requests_to(Endpoint::CreateLimitOrder)[0].body).unwrap();
+    assert_eq!(body["params"]["makingAmount"], "1000000");
+    assert_eq!(body["params"]["takingAmount"], "7000000");
+    assert_eq!(body["computeUnitPrice"], "auto");
+    assert!(body.get("referral").is_none());
+}
+
+#[tokio::test]
+async fn recurring_order_is_created_and_listed() {
+    let server = MockJupiterServer::start();
+    server.push_response(
+        Endpoint::CreateRecurringOrder,
+        MockResponse::Json(serde_json::json!({ "requestId": "1", "transaction": "AQID" })),
+    );
+    server.push_response(
+        Endpoint::RecurringExecute,
+        MockResponse::Json(serde_json::json!({
+            "signature": "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW",
+            "status": "Success",
+            "order": USDC_MINT.to_string(),
+            "error": null,
+        })),
+    );
+    server.push_response(
+        Endpoint::RecurringOrders,
+        MockResponse::Json(serde_json::json!({
+            "user": TEST_WALLET.to_string(),
+            "orderStatus": "active",
+            "time": [{
+                "userPubkey": TEST_WALLET.to_string(),
+                "orderKey": USDC_MINT.to_string(),
+                "inputMint": USDC_MINT.to_string(),
+                "outputMint": NATIVE_MINT.to_string(),
+                "cycleFrequency": "86400",
+                "inDeposited": "30", "inWithdrawn": "0", "inUsed": "10",
+                "inAmountPerCycle": "10", "outReceived": "0.07", "outWithdrawn": "0",
+                "rawInDeposited": "30000000", "rawInWithdrawn": "0", "rawInUsed": "10000000",
+                "rawInAmountPerCycle": "10000000", "rawOutReceived": "70000000",
+                "rawOutWithdrawn": "0", "rawMinOutAmount": null, "rawMaxOutAmount": null,
+                "openTx": "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW",
+                "closeTx": "",
+                "userClosed": false,
+                "createdAt": "2025-01-01T00:00:00", "updatedAt": "2025-01-02T00:00:00",
+                "trades": [],
+            }],
+            "price": [{
+                "userPubkey": TEST_WALLET.to_string(),
+                "orderKey": NATIVE_MINT.to_string(),
+                "inputMint": USDC_MINT.to_string(),
+                "outputMint": NATIVE_MINT.to_string(),
+                "orderInterval": "86400",
+                "incrementalUsdValue": "5",
+                "inDeposited": "20", "inWithdrawn": "0", "inLeft": "15", "inUsed": "5",
+                "outReceived": "0.035", "outWithdrawn": "0",
+                "rawInDeposited": "20000000", "rawInWithdrawn": "0", "rawInLeft": "15000000",
+                "rawInUsed": "5000000", "rawOutReceived": "35000000", "rawOutWithdrawn": "0",
+                "openTx": "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW",
+                "closeTx": "",
+                "createdAt": "2025-01-01T00:00:00", "updatedAt": "2025-01-02T00:00:00",
+                "trades": [],
+            }],
+            "page": 1,
+            "totalPages": 1,
+        })),
+    );
+    let client = JupiterSwapApiClient::builder(server.base_path())
+        .api_base_path(server.base_path())
+        .build()
+        .unwrap();
+
+    let transaction_response = client
+        .create_recurring_order(&CreateRecurringOrderRequest {
+            user: TEST_WALLET,
+            input_mint: USDC_MINT,
+            output_mint: NATIVE_MINT,
+            params: RecurringOrderParams::Time(TimeRecurringParams {
+                min_price: Some(Decimal::new(5, 1)),
+                ..TimeRecurringParams::per_cycle(10_000_000, 3, 86_400).unwrap()
+            }),
+        })
+        .await
+        .unwrap();
+    let execute_response = client
+        .recurring_execute(&ExecuteRequest {
+            signed_transaction: transaction_response.transaction,
+            request_id: transaction_response.request_id,
+        })
+        .await
+        .unwrap();
+    let recurring_orders = client
+        .recurring_orders(&RecurringOrdersRequest {
+            user: TEST_WALLET,
+            ..RecurringOrdersRequest::default()
+        })
+        .await
+        .unwrap();
+
+    let body: serde_json::Value =
+        serde_json::from_str(&server.requests_to(Endpoint::CreateRecurringOrder)[0].body).unwrap();
+    assert_eq!(body["params"]["time"]["inAmount"], 30_000_000);
+    assert_eq!(body["params"]["time"]["numberOfOrders"], 3);
+    assert_eq!(body["params"]["time"]["minPrice"], 0.5);
+    assert!(body["params"]["time"]["maxPrice"].is_null());
+    let body: serde_json::Value =
+        serde_json::from_str(&server.requests_to(Endpoint::RecurringExecute)[0].body).unwrap();
+    assert_eq!(body["requestId"], "1");
+    assert_eq!(body["signedTransaction"], "AQID");
+    assert_eq!(execute_response.status, ExecuteStatus::Success);
+    assert_eq!(execute_response.order, Some(USDC_MINT));
+    assert_eq!(recurring_orders.time[0].raw_in_remaining(), 20_000_000);
+    assert_eq!(recurring_orders.time[0].close_tx, None);
+    assert_eq!(recurring_orders.price[0].raw_in_left, 15_000_000);
+    assert!(TimeRecurringParams::per_cycle(u64::MAX, 2, 86_400).is_none());
+}
+
+#[tokio::test]
+async fn ultra_order_is_signed_and_executed_with_its_request_id() {
+    use solana_sdk::{
+        hash::Hash,
+        message::{v0, VersionedMessage},
+        signature::{Keypair, Signature, Signer},
+        system_instruction,
+        transaction::VersionedTransaction,
+    };
+
+    let taker = Keypair::new();
+    let message = v0::Message::try_compile(
+        &taker.pubkey(),
+        &[system_instruction::transfer(
+            &taker.pubkey(),
+            &TEST_WALLET,
+            1,
+        )],
+        &[],
+        Hash::default(),
+    )
+    .unwrap();
+    let unsigned_transaction = VersionedTransaction {
+        signatures: vec![Signature::default()],
+        message: VersionedMessage::V0(message),
+    };
+    let server = MockJupiterServer::start();
+    server.push_response(
+        Endpoint::UltraOrder,
+        MockResponse::Json(serde_json::json!({
+            "inputMint": USDC_MINT.to_string(),
+            "outputMint": NATIVE_MINT.to_string(),
+            "inAmount": "1000000",
+            "outAmount": "6000000",
+            "otherAmountThreshold": "5970000",
+            "swapMode": "ExactIn",
+            "slippageBps": 50,
+            "priceImpactPct": "0",
+            "routePlan": [],
+            "taker": taker.pubkey().to_string(),
+            "gasless": false,
+            "transaction": base64::encode(bincode::serialize(&unsigned_transaction).unwrap()),
+            "requestId": "request-1",
+            "swapType": "rfq",
+            "quoteId": "quote-1",
+        })),
+    );
+    server.push_response(
+        Endpoint::UltraExecute,
+        MockResponse::Json(serde_json::json!({
+            "status": "Failed",
+            "signature": "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW",
+            "code": -1005,
+            "error": "Transaction expired",
+        })),
+    );
+    let client = JupiterSwapApiClient::builder(server.base_path())
+        .api_base_path(server.base_path())
+        .build()
+        .unwrap();
+
+    let order_response = client
+        .ultra_order(&OrderRequest {
+            input_mint: USDC_MINT,
+            output_mint: NATIVE_MINT,
+            amount: 1_000_000,
+            taker: Some(taker.pubkey()),
+            ..OrderRequest::default()
+        })
+        .await
+        .unwrap();
+    let signed_transaction = order_response.sign(&[&taker]).unwrap();
+    let execute_response = client
+        .ultra_execute(
+            &ExecuteRequest::new(&signed_transaction, order_response.request_id.clone()).unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert!(signed_transaction
+        .verify_with_results()
+        .iter()
+        .all(|ok| *ok));
+    let body: serde_json::Value =
+        serde_json::from_str(&server.requests_to(Endpoint::UltraExecute)[0].body).unwrap();
+    assert_eq!(body["requestId"], "request-1");
+    assert_eq!(execute_response.status, ExecuteStatus::Failed);
+    match execute_response.into_result().unwrap_err() {
+        JupiterError::Ultra(UltraError::ExecuteFailed {
+            code, signature, ..
+        }) => {
+            assert_eq!(code, -1005);
+            assert!(signature.is_some());
+        }
+        error => panic!("unexpected error: {error}"),
+    }
+}
+
+#[tokio::test]
+async fn stale_quote_is_rejected_before_swap() {
+    let server = MockJupiterServer::start();
+    server.push_response(Endpoint::Quote, MockResponse::json(&quote_response()));
+    let mut newer_quote = quote_response();
+    newer_quote.context_slot += 20;
+    server.push_response(Endpoint::Quote, MockResponse::json(&newer_quote));
+    let client = JupiterSwapApiClient::builder(server.base_path())
+        .quote_freshness(QuoteFreshness {
+            max_slot_lag: Some(10),
+            max_age: None,
+        })
+        .build()
+        .unwrap();
+
+    let old_quote_response = client.quote(&quote_request()).await.unwrap();
+    client.quote(&quote_request()).await.unwrap();
+    let error = client
+        .swap(&SwapRequest {
+            user_public_key: TEST_WALLET,
+            quote_response: old_quote_response,
+            config: TransactionConfig::default(),
+        })
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        JupiterError::StaleQuote(StaleQuote { slot_lag: 20, .. })
+    ));
+    assert!(server.requests_to(Endpoint::Swap).is_empty());
+}
+
+#[tokio::test]
+async fn quote_of_unknown_age_is_rejected_with_max_age() {
+    let server = MockJupiterServer::start();
+    let client = JupiterSwapApiClient::builder(server.base_path())
+        .quote_freshness(QuoteFreshness {
+            max_slot_lag: None,
+            max_age: Some(Duration::from_secs(2)),
+        })
+        .build()
+        .unwrap();
+
+    // Deserialized by the caller instead of received by `quote`
+    let error = client
+        .swap(&SwapRequest {
+            user_public_key: TEST_WALLET,
+            quote_response: quote_response(),
+            config: TransactionConfig::default(),
+        })
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        JupiterError::StaleQuote(StaleQuote { age: None, .. })
+    ));
+    assert!(server.requests_to(Endpoint::Swap).is_empty());
+}
@@ -0,0 +1,171 @@
+use jupiter_swap_api_client::{
+    jupiter_instruction::JUPITER_PROGRAM_ID,
+    preflight::{set_compute_unit_limit, simulate, JupiterProgramError},
+    programs::TOKEN_PROGRAM_ID,
+    rpc::{InMemorySolanaRpc, SimulationResult},
+};
+use solana_sdk::{
+    account::Account,
+    compute_budget::{self, ComputeBudgetInstruction},
+    hash::Hash,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    message::{v0, VersionedMessage},
+    pubkey,
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::{TransactionError, VersionedTransaction},
+};
+
+const USDC_MINT: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+const BONK_MINT: Pubkey = pubkey!("DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263");
+const TEST_WALLET: Pubkey = pubkey!("2AQdpHJ2JpcEgPiATUXjQxA8QmafFegfQwSLWSprPicm");
+
+fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> Account {
+    let mut data = vec![0; 165];
+    data[..32].copy_from_slice(mint.as_ref());
+    data[32..64].copy_from_slice(owner.as_ref());
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    Account {
+        lamports: 2_039_280,
+        data,
+        owner: TOKEN_PROGRAM_ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn wallet(lamports: u64) -> Account {
+    Account {
+        lamports,
+        ..Account::default()
+    }
+}
+
+fn transaction(accounts: &[Pubkey]) -> VersionedTransaction {
+    let mut route_accounts = vec![AccountMeta::new(TEST_WALLET, true)];
+    route_accounts.extend(
+        accounts
+            .iter()
+            .map(|account| AccountMeta::new(*account, false)),
+    );
+    let instructions = [
+        ComputeBudgetInstruction::set_compute_unit_limit(1_400_000),
+        ComputeBudgetInstruction::set_compute_unit_price(1_000),
+        Instruction::new_with_bytes(JUPITER_PROGRAM_ID, &[], route_accounts),
+    ];
+    let message =
+        v0::Message::try_compile(&TEST_WALLET, &instructions, &[], Hash::default()).unwrap();
+    VersionedTransaction {
+        signatures: vec![Signature::new_unique()],
+        message: VersionedMessage::V0(message),
+    }
+}
+
+#[tokio::test]
+async fn simulation_report_and_compute_unit_limit() {
+    let usdc_account = Pubkey::new_unique();
+    let bonk_account = Pubkey::new_unique();
+    let pool_account = Pubkey::new_unique();
+    let rpc = InMemorySolanaRpc::new();
+    rpc.set_account(TEST_WALLET, wallet(1_000_000_000));
+    rpc.set_account(
+        usdc_account,
+        token_account(USDC_MINT, TEST_WALLET, 5_000_000),
+    );
+    rpc.set_account(
+        pool_account,
+        token_account(USDC_MINT, Pubkey::new_unique(), 1),
+    );
+    rpc.set_simulated_account(TEST_WALLET, wallet(1_000_000_000 - 2_044_280));
+    rpc.set_simulated_account(
+        usdc_account,
+        token_account(USDC_MINT, TEST_WALLET, 4_000_000),
+    );
+    rpc.set_simulated_account(
+        bonk_account,
+        token_account(BONK_MINT, TEST_WALLET, 1_980_000),
+    );
+    rpc.set_simulated_account(
+        pool_account,
+        token_account(USDC_MINT, Pubkey::new_unique(), 1_000_001),
+    );
+    rpc.push_simulation_result(SimulationResult {
+        units_consumed: Some(120_000),
+        ..SimulationResult::default()
+    });
+
+    let mut transaction = transaction(&[usdc_account, bonk_account, pool_account]);
+    let report = simulate(&rpc, &transaction, &TEST_WALLET)
+        .await
+        .unwrap()
+        .into_result()
+        .unwrap();
+    assert_eq!(report.lamport_change, Some(-2_044_280));
+    assert_eq!(report.token_balance_change(&USDC_MINT), -1_000_000);
+    assert_eq!(report.token_balance_change(&BONK_MINT), 1_980_000);
+    assert_eq!(report.token_balance_deltas.len(), 2);
+    assert_eq!(report.compute_unit_limit(1_000), Some(132_000));
+
+    set_compute_unit_limit(&mut transaction, 132_000).unwrap();
+    let message = &transaction.message;
+    let compute_budget_instruction = &message.instructions()[0];
+    assert_eq!(
+        compute_budget_instruction.program_id(message.static_account_keys()),
+        &compute_budget::id()
+    );
+    assert_eq!(
+        compute_budget_instruction.data,
+        ComputeBudgetInstruction::set_compute_unit_limit(132_000).data
+    );
+    assert_eq!(
+        message.instructions()[1].data,
+        ComputeBudgetInstruction::set_compute_unit_price(1_000).data
+    );
+    assert_eq!(transaction.signatures, vec![Signature::default()]);
+}
+
+#[tokio::test]
+async fn jupiter_program_error_is_decoded() {
+    let rpc = InMemorySolanaRpc::new();
+    rpc.push_simulation_result(SimulationResult {
+        err: Some(TransactionError::InstructionError(
+            2,
+            InstructionError::Custom(6001),
+        )),
+        logs: vec!["Program log: Error: SlippageToleranceExceeded".to_string()],
+        ..SimulationResult::default()
+    });
+
+    let report = simulate(&rpc, &transaction(&[]), &TEST_WALLET)
+        .await
+        .unwrap();
+    let program_error = report.program_error.clone().unwrap();
+    assert_eq!(program_error.program_id, Some(JUPITER_PROGRAM_ID));
+    assert_eq!(
+        program_error.jupiter_error,
+        Some(JupiterProgramError::SlippageToleranceExceeded)
+    );
+    assert!(report.into_result().is_err());
+}
+
+#[tokio::test]
+async fn out_of_range_program_index_is_not_decoded() {
+    let rpc = InMemorySolanaRpc::new();
+    rpc.push_simulation_result(SimulationResult {
+        err: Some(TransactionError::InstructionError(
+            2,
+            InstructionError::Custom(6001),
+        )),
+        ..SimulationResult::default()
+    });
+    let mut transaction = transaction(&[]);
+    let VersionedMessage::V0(message) = &mut transaction.message else {
+        unreachable!()
+    };
+    message.instructions[2].program_id_index = 9;
+
+    let report = simulate(&rpc, &transaction, &TEST_WALLET).await.unwrap();
+    let program_error = report.program_error.unwrap();
+    assert_eq!(program_error.program_id, None);
+    assert_eq!(program_error.jupiter_error, None);
+}
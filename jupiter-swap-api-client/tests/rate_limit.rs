@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use jupiter_swap_api_client::{
+    endpoint::Endpoint,
+    error::JupiterError,
+    rate_limit::{RateLimit, RateLimitMode, RateLimiter},
+};
+use tokio::time::Instant;
+
+fn rate_limit(requests_per_second: f64, burst: u32) -> RateLimit {
+    RateLimit::new(requests_per_second, burst).unwrap()
+}
+
+#[tokio::test(start_paused = true)]
+async fn burst_then_refill() {
+    let rate_limiter =
+        RateLimiter::new(RateLimitMode::FailFast).limit(Endpoint::Quote, rate_limit(2.0, 2));
+
+    rate_limiter.acquire(Endpoint::Quote).await.unwrap();
+    rate_limiter.acquire(Endpoint::Quote).await.unwrap();
+    assert!(matches!(
+        rate_limiter.acquire(Endpoint::Quote).await,
+        Err(JupiterError::RateLimited(Endpoint::Quote))
+    ));
+    // Endpoints without a limit are not throttled
+    for _ in 0..10 {
+        rate_limiter.acquire(Endpoint::Swap).await.unwrap();
+    }
+
+    tokio::time::advance(Duration::from_millis(500)).await;
+    rate_limiter.acquire(Endpoint::Quote).await.unwrap();
+    assert!(rate_limiter.acquire(Endpoint::Quote).await.is_err());
+
+    // The refill is capped at the burst
+    tokio::time::advance(Duration::from_secs(60)).await;
+    for _ in 0..2 {
+        rate_limiter.acquire(Endpoint::Quote).await.unwrap();
+    }
+    assert!(rate_limiter.acquire(Endpoint::Quote).await.is_err());
+}
+
+#[tokio::test(start_paused = true)]
+async fn wait_mode_spaces_requests() {
+    let rate_limiter =
+        RateLimiter::new(RateLimitMode::Wait).limit(Endpoint::Quote, rate_limit(4.0, 1));
+
+    let start = Instant::now();
+    for _ in 0..5 {
+        rate_limiter.acquire(Endpoint::Quote).await.unwrap();
+    }
+    assert_eq!(start.elapsed(), Duration::from_secs(1));
+}
+
+#[tokio::test(start_paused = true)]
+async fn per_endpoint_limits_every_endpoint_separately() {
+    let rate_limiter = RateLimiter::per_endpoint(rate_limit(1.0, 1), RateLimitMode::FailFast);
+
+    for endpoint in Endpoint::ALL {
+        rate_limiter.acquire(*endpoint).await.unwrap();
+    }
+    for endpoint in Endpoint::ALL {
+        assert!(rate_limiter.acquire(*endpoint).await.is_err());
+    }
+}
+
+#[test]
+fn invalid_rate_limits_are_rejected() {
+    for requests_per_second in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        assert!(matches!(
+            RateLimit::new(requests_per_second, 1),
+            Err(JupiterError::InvalidRateLimit(_))
+        ));
+    }
+    assert!(RateLimit::new(1.0, 0).is_err());
+}
+
+#[tokio::test(start_paused = true)]
+async fn tiny_rate_waits_without_overflowing() {
+    let rate_limiter = RateLimiter::new(RateLimitMode::Wait)
+        .limit(Endpoint::Quote, rate_limit(f64::MIN_POSITIVE, 1));
+
+    rate_limiter.acquire(Endpoint::Quote).await.unwrap();
+    assert!(tokio::time::timeout(
+        Duration::from_secs(1),
+        rate_limiter.acquire(Endpoint::Quote)
+    )
+    .await
+    .is_err());
+}
@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+use jupiter_swap_api_client::{error::JupiterError, retry::RetryPolicy};
+use reqwest::StatusCode;
+
+fn rate_limited(retry_after: Option<Duration>) -> JupiterError {
+    JupiterError::Api {
+        status: StatusCode::TOO_MANY_REQUESTS,
+        error_code: None,
+        body: String::new(),
+        retry_after,
+    }
+}
+
+#[test]
+fn retry_after_is_capped() {
+    let retry_policy = RetryPolicy {
+        jitter: false,
+        ..RetryPolicy::default()
+    };
+
+    assert_eq!(
+        retry_policy.delay(0, &rate_limited(Some(Duration::from_secs(3)))),
+        Duration::from_secs(3)
+    );
+    assert_eq!(
+        retry_policy.delay(0, &rate_limited(Some(Duration::from_secs(86_400)))),
+        retry_policy.max_retry_after
+    );
+    assert_eq!(
+        retry_policy.delay(10, &rate_limited(None)),
+        retry_policy.max_backoff
+    );
+}
@@ -0,0 +1,112 @@
+use std::collections::HashSet;
+
+use jupiter_swap_api_client::{
+    error::JupiterError,
+    quote::QuoteResponse,
+    route_export::RouteExporter,
+    route_graph::{RouteError, RouteGraph},
+};
+use solana_sdk::{pubkey, pubkey::Pubkey};
+
+const USDC_MINT: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+const NATIVE_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+const MSOL_MINT: Pubkey = pubkey!("mSoLzYCxHdYgdzU16g5QSh3i5K3z3KZK7ytfqcJm7So");
+
+fn step(
+    amm_key: Pubkey,
+    label: &str,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    percent: u8,
+) -> serde_json::Value {
+    serde_json::json!({
+        "swapInfo": {
+            "ammKey": amm_key.to_string(),
+            "label": label,
+            "inputMint": input_mint.to_string(),
+            "outputMint": output_mint.to_string(),
+            "inAmount": "0",
+            "outAmount": "0",
+            "feeAmount": "10",
+            "feeMint": input_mint.to_string(),
+        },
+        "percent": percent,
+    })
+}
+
+/// USDC splits 60/40 between a direct pool and a hop through mSOL
+fn quote_response(direct_percent: u8) -> QuoteResponse {
+    serde_json::from_value(serde_json::json!({
+        "inputMint": USDC_MINT.to_string(),
+        "inAmount": "1000000",
+        "outputMint": NATIVE_MINT.to_string(),
+        "outAmount": "6000000",
+        "otherAmountThreshold": "5970000",
+        "swapMode": "ExactIn",
+        "slippageBps": 50,
+        "priceImpactPct": "0",
+        "routePlan": [
+            step(Pubkey::new_unique(), "Whirlpool", USDC_MINT, NATIVE_MINT, direct_percent),
+            step(Pubkey::new_unique(), "Raydium", USDC_MINT, MSOL_MINT, 40),
+            step(Pubkey::new_unique(), "Sanctum", MSOL_MINT, NATIVE_MINT, 100),
+        ],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn split_route_statistics() {
+    let quote_response = quote_response(60);
+    let route_graph = RouteGraph::from_quote(&quote_response);
+
+    route_graph.validate_splits().unwrap();
+    assert_eq!(route_graph.intermediate_mints(), vec![MSOL_MINT]);
+    assert_eq!(route_graph.hop_count(), 2);
+    assert_eq!(route_graph.amm_keys().len(), 3);
+    let fees = route_graph.fees_by_mint().unwrap();
+    assert_eq!(fees[&USDC_MINT], 20);
+    assert_eq!(fees[&MSOL_MINT], 10);
+}
+
+#[test]
+fn invalid_splits_and_disallowed_mints_are_flagged() {
+    let quote_response = quote_response(50);
+    let route_graph = RouteGraph::from_quote(&quote_response);
+
+    assert!(matches!(
+        route_graph.validate_splits(),
+        Err(JupiterError::Route(RouteError::InvalidSplit { mint, total_percent: 90 })) if mint == USDC_MINT
+    ));
+    assert!(matches!(
+        route_graph.check_allowlist(&HashSet::new()),
+        Err(JupiterError::Route(RouteError::MintNotAllowed(mint))) if mint == MSOL_MINT
+    ));
+    route_graph
+        .check_allowlist(&HashSet::from([MSOL_MINT]))
+        .unwrap();
+}
+
+#[test]
+fn route_is_exported_as_dot_mermaid_and_ascii_tree() {
+    let quote_response = quote_response(60);
+    let exporter = RouteExporter::new(RouteGraph::from_quote(&quote_response))
+        .symbol(USDC_MINT, "USDC".into())
+        .symbol(NATIVE_MINT, "SOL".into());
+
+    let dot = exporter.dot();
+    assert!(dot.contains("n0 [label=\"USDC\"];"));
+    assert!(dot.contains("n2 [label=\"mSoL…m7So\"];"));
+    assert!(dot.contains("n0 -> n1 [label=\"Whirlpool 60%\\nin 0, out 0\\nfee 10 USDC\"];"));
+
+    let mermaid = exporter.mermaid();
+    assert!(mermaid.starts_with("flowchart LR\n"));
+    assert!(mermaid.contains("n2 -->|\"Sanctum 100%<br/>in 0, out 0<br/>fee 10 mSoL…m7So\"| n1"));
+
+    assert_eq!(
+        exporter.ascii_tree(),
+        "USDC\n\
+         ├── Whirlpool 60% → SOL (in 0, out 0, fee 10 USDC)\n\
+         └── Raydium 40% → mSoL…m7So (in 0, out 0, fee 10 USDC)\n\
+         \x20   └── Sanctum 100% → SOL (in 0, out 0, fee 10 mSoL…m7So)\n"
+    );
+}
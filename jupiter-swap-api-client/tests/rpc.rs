@@ -0,0 +1,74 @@
+use std::borrow::Cow;
+
+use jupiter_swap_api_client::{
+    error::JupiterError,
+    rpc::{deserialize_address_lookup_table, InMemorySolanaRpc, RpcError, SolanaRpc},
+};
+use solana_address_lookup_table_program::state::{AddressLookupTable, LookupTableMeta};
+use solana_sdk::pubkey::Pubkey;
+
+fn serialized_table(addresses: &[Pubkey]) -> Vec<u8> {
+    AddressLookupTable {
+        meta: LookupTableMeta::new(Pubkey::new_unique()),
+        addresses: Cow::Borrowed(addresses),
+    }
+    .serialize_for_tests()
+    .unwrap()
+}
+
+#[test]
+fn address_lookup_table_round_trip() {
+    let key = Pubkey::new_unique();
+    let addresses = (0..3).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+
+    let table = deserialize_address_lookup_table(key, &serialized_table(&addresses)).unwrap();
+    assert_eq!(table.key, key);
+    assert_eq!(table.addresses, addresses);
+
+    let empty = deserialize_address_lookup_table(key, &serialized_table(&[])).unwrap();
+    assert!(empty.addresses.is_empty());
+}
+
+#[test]
+fn malformed_address_lookup_table_is_rejected() {
+    let key = Pubkey::new_unique();
+    let data = serialized_table(&[Pubkey::new_unique()]);
+
+    for truncated in [&data[..0], &data[..3], &data[..40], &data[..data.len() - 1]] {
+        assert!(matches!(
+            deserialize_address_lookup_table(key, truncated),
+            Err(JupiterError::Rpc(RpcError::InvalidAddressLookupTable(invalid))) if invalid == key
+        ));
+    }
+    // An uninitialized table
+    let mut uninitialized = data.clone();
+    uninitialized[..4].copy_from_slice(&0u32.to_le_bytes());
+    assert!(deserialize_address_lookup_table(key, &uninitialized).is_err());
+}
+
+#[tokio::test]
+async fn in_memory_serves_address_lookup_tables_in_order() {
+    let rpc = InMemorySolanaRpc::new();
+    let tables = (0..2)
+        .map(|_| {
+            deserialize_address_lookup_table(
+                Pubkey::new_unique(),
+                &serialized_table(&[Pubkey::new_unique()]),
+            )
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+    tables
+        .iter()
+        .for_each(|table| rpc.add_address_lookup_table(table.clone()));
+
+    let keys = [tables[1].key, tables[0].key];
+    let fetched = rpc.get_address_lookup_tables(&keys).await.unwrap();
+    assert_eq!(fetched, vec![tables[1].clone(), tables[0].clone()]);
+
+    let missing = Pubkey::new_unique();
+    assert!(matches!(
+        rpc.get_address_lookup_tables(&[tables[0].key, missing]).await,
+        Err(JupiterError::Rpc(RpcError::AccountNotFound(key))) if key == missing
+    ));
+}
@@ -0,0 +1,120 @@
+use jupiter_swap_api_client::{
+    error::JupiterError, swap::SwapResponse, transaction::SwapTransactionError,
+};
+use solana_sdk::{
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    message::{v0, Message, VersionedMessage},
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::VersionedTransaction,
+};
+
+fn instruction(signers: &[Pubkey]) -> Instruction {
+    Instruction::new_with_bytes(
+        Pubkey::new_unique(),
+        &[1, 2, 3],
+        signers
+            .iter()
+            .map(|signer| AccountMeta::new(*signer, true))
+            .collect(),
+    )
+}
+
+fn swap_response(message: VersionedMessage) -> SwapResponse {
+    let transaction = VersionedTransaction {
+        signatures: vec![Signature::default(); message.header().num_required_signatures as usize],
+        message,
+    };
+    SwapResponse {
+        swap_transaction: bincode::serialize(&transaction).unwrap(),
+        last_valid_block_height: 42,
+    }
+}
+
+#[test]
+fn sign_keeps_other_signatures() {
+    let user = Keypair::new();
+    let cosigner = Keypair::new();
+    let message = v0::Message::try_compile(
+        &user.pubkey(),
+        &[instruction(&[user.pubkey(), cosigner.pubkey()])],
+        &[],
+        Hash::new_unique(),
+    )
+    .unwrap();
+    let swap_response = swap_response(VersionedMessage::V0(message));
+    let cosigned = swap_response.sign(&user.pubkey(), &[&cosigner]).unwrap();
+    let swap_response = SwapResponse {
+        swap_transaction: bincode::serialize(&cosigned).unwrap(),
+        ..swap_response
+    };
+
+    let transaction = swap_response.sign(&user.pubkey(), &[&user]).unwrap();
+
+    assert!(transaction
+        .verify_with_results()
+        .into_iter()
+        .all(|verified| verified));
+    assert_eq!(transaction.signatures[1], cosigned.signatures[1]);
+}
+
+#[test]
+fn sign_rejects_unexpected_signers() {
+    let user = Keypair::new();
+    let other = Keypair::new();
+    let message = v0::Message::try_compile(
+        &user.pubkey(),
+        &[instruction(&[user.pubkey()])],
+        &[],
+        Hash::new_unique(),
+    )
+    .unwrap();
+    let swap_response = swap_response(VersionedMessage::V0(message));
+
+    match swap_response.sign(&other.pubkey(), &[&other]) {
+        Err(JupiterError::Transaction(SwapTransactionError::UnexpectedFeePayer {
+            expected,
+            found,
+        })) => {
+            assert_eq!(expected, other.pubkey());
+            assert_eq!(found, Some(user.pubkey()));
+        }
+        result => panic!("unexpected result: {result:?}"),
+    }
+    assert!(matches!(
+        swap_response.sign(&user.pubkey(), &[&user, &other]),
+        Err(JupiterError::Transaction(SwapTransactionError::SignerNotRequired(signer))) if signer == other.pubkey()
+    ));
+}
+
+#[test]
+fn legacy_transaction_is_decoded() {
+    let user = Keypair::new();
+    let message = Message::new_with_blockhash(
+        &[instruction(&[user.pubkey()])],
+        Some(&user.pubkey()),
+        &Hash::new_unique(),
+    );
+    let legacy = swap_response(VersionedMessage::Legacy(message.clone()));
+
+    let transaction = legacy.legacy_transaction().unwrap();
+    assert_eq!(transaction.message, message);
+    let signed = legacy.sign(&user.pubkey(), &[&user]).unwrap();
+    assert!(matches!(signed.message, VersionedMessage::Legacy(_)));
+    assert!(signed
+        .verify_with_results()
+        .into_iter()
+        .all(|verified| verified));
+
+    let v0_message =
+        v0::Message::try_compile(&user.pubkey(), &[instruction(&[])], &[], Hash::new_unique())
+            .unwrap();
+    assert!(matches!(
+        swap_response(VersionedMessage::V0(v0_message)).legacy_transaction(),
+        Err(JupiterError::Transaction(
+            SwapTransactionError::NotLegacyTransaction
+        ))
+    ));
+}
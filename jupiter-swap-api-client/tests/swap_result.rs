@@ -0,0 +1,304 @@
+use jupiter_swap_api_client::{
+    jupiter_instruction::JUPITER_PROGRAM_ID,
+    quote::QuoteResponse,
+    rpc::{ConfirmedTransaction, InnerInstructions, TokenBalance},
+    swap_result::{
+        FeeEvent, SwapEvent, SwapResult, EVENT_IX_TAG, FEE_EVENT_DISCRIMINATOR,
+        SWAP_EVENT_DISCRIMINATOR,
+    },
+    Decimal,
+};
+use solana_sdk::{
+    hash::Hash,
+    instruction::{AccountMeta, CompiledInstruction, Instruction},
+    message::{v0, v0::LoadedAddresses, VersionedMessage},
+    pubkey,
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+
+const USDC_MINT: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+const BONK_MINT: Pubkey = pubkey!("DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263");
+const NATIVE_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+const TEST_WALLET: Pubkey = pubkey!("2AQdpHJ2JpcEgPiATUXjQxA8QmafFegfQwSLWSprPicm");
+const FIRST_AMM: Pubkey = pubkey!("Czfq3xZZDmsdGdUyrNLtRhGc47cXcZtLG4crryfu44zE");
+const SECOND_AMM: Pubkey = pubkey!("HTvjzsfX3yU6BUodCjZ5vZkUrAxMDTrBs3CJaq43ashR");
+
+fn quote_response() -> QuoteResponse {
+    serde_json::from_value(serde_json::json!({
+        "inputMint": USDC_MINT.to_string(),
+        "inAmount": "1000000",
+        "outputMint": BONK_MINT.to_string(),
+        "outAmount": "2000000",
+        "otherAmountThreshold": "1990000",
+        "swapMode": "ExactIn",
+        "slippageBps": 50,
+        "priceImpactPct": "0",
+        "routePlan": [
+            {
+                "swapInfo": {
+                    "ammKey": FIRST_AMM.to_string(),
+                    "label": "Whirlpool",
+                    "inputMint": USDC_MINT.to_string(),
+                    "outputMint": NATIVE_MINT.to_string(),
+                    "inAmount": "1000000",
+                    "outAmount": "6000000",
+                    "feeAmount": "100",
+                    "feeMint": USDC_MINT.to_string(),
+                },
+                "percent": 100,
+            },
+            {
+                "swapInfo": {
+                    "ammKey": SECOND_AMM.to_string(),
+                    "label": "Meteora DLMM",
+                    "inputMint": NATIVE_MINT.to_string(),
+                    "outputMint": BONK_MINT.to_string(),
+                    "inAmount": "6000000",
+                    "outAmount": "2000000",
+                    "feeAmount": "600",
+                    "feeMint": NATIVE_MINT.to_string(),
+                },
+                "percent": 100,
+            },
+        ],
+    }))
+    .unwrap()
+}
+
+fn swap_event(amm: Pubkey, input: (Pubkey, u64), output: (Pubkey, u64)) -> Vec<u8> {
+    let mut data = EVENT_IX_TAG.to_vec();
+    data.extend(SWAP_EVENT_DISCRIMINATOR);
+    data.extend(amm.to_bytes());
+    data.extend(input.0.to_bytes());
+    data.extend(input.1.to_le_bytes());
+    data.extend(output.0.to_bytes());
+    data.extend(output.1.to_le_bytes());
+    data
+}
+
+fn token_balance(account_index: u8, mint: Pubkey, amount: u64) -> TokenBalance {
+    TokenBalance {
+        account_index,
+        mint,
+        owner: Some(TEST_WALLET),
+        amount,
+    }
+}
+
+fn confirmed_transaction() -> ConfirmedTransaction {
+    let route = Instruction::new_with_bytes(
+        JUPITER_PROGRAM_ID,
+        &[],
+        vec![AccountMeta::new(TEST_WALLET, true)],
+    );
+    let message = v0::Message::try_compile(&TEST_WALLET, &[route], &[], Hash::default()).unwrap();
+    let jupiter_program_index = message
+        .account_keys
+        .iter()
+        .position(|key| key == &JUPITER_PROGRAM_ID)
+        .unwrap() as u8;
+    let fee_account = Pubkey::new_unique();
+    let mut fee_event = EVENT_IX_TAG.to_vec();
+    fee_event.extend(FEE_EVENT_DISCRIMINATOR);
+    fee_event.extend(fee_account.to_bytes());
+    fee_event.extend(BONK_MINT.to_bytes());
+    fee_event.extend(1_000u64.to_le_bytes());
+    let event_instruction = |data: Vec<u8>| CompiledInstruction {
+        program_id_index: jupiter_program_index,
+        accounts: vec![],
+        data,
+    };
+
+    ConfirmedTransaction {
+        slot: 1,
+        transaction: VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::V0(message),
+        },
+        loaded_addresses: LoadedAddresses::default(),
+        err: None,
+        fee: 5_000,
+        pre_balances: vec![],
+        post_balances: vec![],
+        pre_token_balances: vec![
+            token_balance(2, USDC_MINT, 5_000_000),
+            token_balance(3, BONK_MINT, 0),
+        ],
+        post_token_balances: vec![
+            token_balance(2, USDC_MINT, 4_000_000),
+            token_balance(3, BONK_MINT, 1_980_000),
+        ],
+        log_messages: vec![],
+        inner_instructions: vec![InnerInstructions {
+            index: 0,
+            instructions: vec![
+                event_instruction(swap_event(
+                    FIRST_AMM,
+                    (USDC_MINT, 1_000_000),
+                    (NATIVE_MINT, 5_990_000),
+                )),
+                event_instruction(swap_event(
+                    SECOND_AMM,
+                    (NATIVE_MINT, 5_990_000),
+                    (BONK_MINT, 1_981_000),
+                )),
+                event_instruction(fee_event),
+            ],
+        }],
+    }
+}
+
+#[test]
+fn parse_and_reconcile_swap_result() {
+    let quote_response = quote_response();
+    let swap_result = SwapResult::parse(
+        &confirmed_transaction(),
+        &TEST_WALLET,
+        &USDC_MINT,
+        &BONK_MINT,
+    )
+    .unwrap();
+
+    assert_eq!(swap_result.in_amount, 1_000_000);
+    assert_eq!(swap_result.out_amount, 1_980_000);
+    assert_eq!(
+        swap_result.hops[1],
+        SwapEvent {
+            amm: SECOND_AMM,
+            input_mint: NATIVE_MINT,
+            input_amount: 5_990_000,
+            output_mint: BONK_MINT,
+            output_amount: 1_981_000,
+        }
+    );
+    assert_eq!(swap_result.fees[0].amount, 1_000);
+    assert!(matches!(
+        swap_result.fees[0],
+        FeeEvent {
+            mint: BONK_MINT,
+            ..
+        }
+    ));
+    assert_eq!(
+        swap_result.slippage_bps(&quote_response),
+        Some(Decimal::from(100))
+    );
+
+    let reconciliation = swap_result.reconcile(&quote_response);
+    assert_eq!(reconciliation.quoted_out_amount, 2_000_000);
+    assert_eq!(
+        reconciliation
+            .hops
+            .iter()
+            .map(|hop| hop.quoted.as_ref().map(|quoted| quoted.label.as_str()))
+            .collect::<Vec<_>>(),
+        vec![Some("Whirlpool"), Some("Meteora DLMM")]
+    );
+}
+
+#[test]
+fn events_are_read_from_program_data_logs() {
+    let mut confirmed_transaction = confirmed_transaction();
+    let events = confirmed_transaction
+        .inner_instructions
+        .remove(0)
+        .instructions;
+    confirmed_transaction.log_messages = events
+        .iter()
+        .map(|event| format!("Program data: {}", base64::encode(&event.data[8..])))
+        .collect();
+
+    let swap_result =
+        SwapResult::parse(&confirmed_transaction, &TEST_WALLET, &USDC_MINT, &BONK_MINT).unwrap();
+    assert_eq!(swap_result.hops.len(), 2);
+    assert_eq!(swap_result.fees.len(), 1);
+}
+
+#[test]
+fn sol_amounts_exclude_created_account_rent_and_tips() {
+    const RENT: u64 = 2_039_280;
+    let bonk_account = Pubkey::new_unique();
+    let tip_account = Pubkey::new_unique();
+    let route = Instruction::new_with_bytes(
+        JUPITER_PROGRAM_ID,
+        &[],
+        vec![
+            AccountMeta::new(TEST_WALLET, true),
+            AccountMeta::new(bonk_account, false),
+            AccountMeta::new(tip_account, false),
+        ],
+    );
+    let message = v0::Message::try_compile(&TEST_WALLET, &[route], &[], Hash::default()).unwrap();
+    let index = |key: &Pubkey| {
+        message
+            .account_keys
+            .iter()
+            .position(|account_key| account_key == key)
+            .unwrap()
+    };
+    let mut pre_balances = vec![0; message.account_keys.len()];
+    pre_balances[index(&TEST_WALLET)] = 10_000_000_000;
+    let mut post_balances = vec![0; message.account_keys.len()];
+    // 1 SOL swapped, the fee, the rent of the BONK account and a tip paid
+    post_balances[index(&TEST_WALLET)] = 10_000_000_000 - 1_000_000_000 - 5_000 - RENT - 10_000;
+    post_balances[index(&bonk_account)] = RENT;
+    post_balances[index(&tip_account)] = 10_000;
+    let mut confirmed_transaction = ConfirmedTransaction {
+        slot: 1,
+        inner_instructions: vec![InnerInstructions {
+            index: 0,
+            instructions: vec![CompiledInstruction {
+                program_id_index: index(&JUPITER_PROGRAM_ID) as u8,
+                accounts: vec![],
+                data: swap_event(
+                    FIRST_AMM,
+                    (NATIVE_MINT, 1_000_000_000),
+                    (BONK_MINT, 1_981_000),
+                ),
+            }],
+        }],
+        transaction: VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::V0(message.clone()),
+        },
+        loaded_addresses: LoadedAddresses::default(),
+        err: None,
+        fee: 5_000,
+        pre_balances,
+        post_balances,
+        pre_token_balances: vec![],
+        post_token_balances: vec![token_balance(
+            index(&bonk_account) as u8,
+            BONK_MINT,
+            1_981_000,
+        )],
+        log_messages: vec![],
+    };
+
+    let swap_result = SwapResult::parse(
+        &confirmed_transaction,
+        &TEST_WALLET,
+        &NATIVE_MINT,
+        &BONK_MINT,
+    )
+    .unwrap();
+    assert_eq!(swap_result.in_amount, 1_000_000_000);
+    assert_eq!(swap_result.out_amount, 1_981_000);
+
+    // Without events the rent is still excluded, the tip is not
+    confirmed_transaction.inner_instructions.clear();
+    assert_eq!(
+        confirmed_transaction.swap_balance_change(&TEST_WALLET, &NATIVE_MINT),
+        -1_000_010_000
+    );
+    let swap_result = SwapResult::parse(
+        &confirmed_transaction,
+        &TEST_WALLET,
+        &NATIVE_MINT,
+        &BONK_MINT,
+    )
+    .unwrap();
+    assert_eq!(swap_result.in_amount, 1_000_010_000);
+}
@@ -0,0 +1,356 @@
+use jupiter_swap_api_client::{
+    error::JupiterError,
+    jupiter_instruction::{JupiterInstructionKind, JUPITER_PROGRAM_ID},
+    programs::{associated_token_address, ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID},
+    quote::{PlatformFee, QuoteResponse},
+    swap::SwapRequest,
+    transaction_config::TransactionConfig,
+    verifier::{SwapVerifier, VerificationError, SET_TOKEN_LEDGER_DISCRIMINATOR},
+};
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    message::{v0, VersionedMessage},
+    pubkey,
+    pubkey::Pubkey,
+    signature::Signature,
+    system_instruction, system_program,
+    transaction::VersionedTransaction,
+};
+
+const USDC_MINT: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+const NATIVE_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+const TEST_WALLET: Pubkey = pubkey!("2AQdpHJ2JpcEgPiATUXjQxA8QmafFegfQwSLWSprPicm");
+
+fn swap_request() -> SwapRequest {
+    let quote_response: QuoteResponse = serde_json::from_value(serde_json::json!({
+        "inputMint": USDC_MINT.to_string(),
+        "inAmount": "1000000",
+        "outputMint": NATIVE_MINT.to_string(),
+        "outAmount": "6000000",
+        "otherAmountThreshold": "5970000",
+        "swapMode": "ExactIn",
+        "slippageBps": 50,
+        "priceImpactPct": "0",
+        "routePlan": [],
+    }))
+    .unwrap();
+    SwapRequest {
+        user_public_key: TEST_WALLET,
+        quote_response,
+        config: TransactionConfig::default(),
+    }
+}
+
+/// `route` with an empty route plan, swapping into the user's wrapped SOL account
+fn route_instruction(in_amount: u64, quoted_out_amount: u64, slippage_bps: u16) -> Instruction {
+    let mut data = JupiterInstructionKind::Route.discriminator().to_vec();
+    data.extend(0u32.to_le_bytes());
+    data.extend(in_amount.to_le_bytes());
+    data.extend(quoted_out_amount.to_le_bytes());
+    data.extend(slippage_bps.to_le_bytes());
+    data.push(0);
+    Instruction::new_with_bytes(
+        JUPITER_PROGRAM_ID,
+        &data,
+        vec![
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(TEST_WALLET, true),
+            AccountMeta::new(
+                associated_token_address(&TEST_WALLET, &USDC_MINT, &TOKEN_PROGRAM_ID),
+                false,
+            ),
+            AccountMeta::new(wrapped_sol_account(), false),
+            AccountMeta::new_readonly(JUPITER_PROGRAM_ID, false),
+            AccountMeta::new_readonly(NATIVE_MINT, false),
+            AccountMeta::new_readonly(JUPITER_PROGRAM_ID, false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(JUPITER_PROGRAM_ID, false),
+        ],
+    )
+}
+
+fn transaction(instructions: &[Instruction]) -> VersionedTransaction {
+    let message =
+        v0::Message::try_compile(&TEST_WALLET, instructions, &[], Hash::default()).unwrap();
+    VersionedTransaction {
+        signatures: vec![Signature::default(); message.header.num_required_signatures as usize],
+        message: VersionedMessage::V0(message),
+    }
+}
+
+fn verification_error(result: Result<(), JupiterError>) -> VerificationError {
+    match result.unwrap_err() {
+        JupiterError::Verification(error) => error,
+        error => panic!("unexpected error: {error}"),
+    }
+}
+
+#[test]
+fn matching_transaction_is_accepted() {
+    let swap_request = swap_request();
+    let transaction = transaction(&[
+        ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+        route_instruction(1_000_000, 6_000_000, 50),
+    ]);
+
+    SwapVerifier::new(&swap_request)
+        .verify_transaction(&transaction)
+        .unwrap();
+}
+
+#[test]
+fn tampered_amounts_are_rejected() {
+    let swap_request = swap_request();
+    let verifier = SwapVerifier::new(&swap_request);
+
+    assert_eq!(
+        verification_error(
+            verifier
+                .verify_transaction(&transaction(&[route_instruction(2_000_000, 6_000_000, 50)]))
+        ),
+        VerificationError::AmountMismatch {
+            field: "in_amount",
+            expected: 1_000_000,
+            found: 2_000_000,
+        }
+    );
+    assert_eq!(
+        verification_error(
+            verifier.verify_transaction(&transaction(&[route_instruction(
+                1_000_000, 6_000_000, 5_000
+            )]))
+        ),
+        VerificationError::SlippageBoundExceeded {
+            expected: 5_970_000,
+            found: 3_000_000,
+        }
+    );
+}
+
+#[test]
+fn unexpected_programs_and_transfers_are_rejected() {
+    let swap_request = swap_request();
+    let verifier = SwapVerifier::new(&swap_request);
+    let attacker = Pubkey::new_unique();
+
+    assert_eq!(
+        verification_error(verifier.verify_transaction(&transaction(&[
+            route_instruction(1_000_000, 6_000_000, 50),
+            system_instruction::transfer(&TEST_WALLET, &attacker, 1_000_000_000),
+        ]))),
+        VerificationError::UnexpectedTransfer {
+            destination: attacker,
+            lamports: 1_000_000_000,
+        }
+    );
+    let unknown_program = Pubkey::new_unique();
+    assert_eq!(
+        verification_error(verifier.verify_transaction(&transaction(&[
+            route_instruction(1_000_000, 6_000_000, 50),
+            Instruction::new_with_bytes(unknown_program, &[], vec![]),
+        ]))),
+        VerificationError::ProgramNotAllowed(unknown_program)
+    );
+}
+
+#[test]
+fn other_transfer_authority_is_rejected() {
+    let swap_request = swap_request();
+    let verifier = SwapVerifier::new(&swap_request);
+    let attacker = Pubkey::new_unique();
+
+    let mut other_authority = route_instruction(1_000_000, 6_000_000, 50);
+    other_authority.accounts[1] = AccountMeta::new_readonly(attacker, true);
+    assert_eq!(
+        verification_error(verifier.verify_transaction(&transaction(&[other_authority]))),
+        VerificationError::UnexpectedSigner(attacker)
+    );
+
+    let mut other_authority = route_instruction(1_000_000, 6_000_000, 50);
+    other_authority.accounts[1] = AccountMeta::new_readonly(attacker, false);
+    assert_eq!(
+        verification_error(verifier.verify_transaction(&transaction(&[other_authority]))),
+        VerificationError::UnexpectedTransferAuthority(attacker)
+    );
+
+    let mut missing_authority = route_instruction(1_000_000, 6_000_000, 50);
+    missing_authority.accounts.truncate(1);
+    assert_eq!(
+        verification_error(verifier.verify_transaction(&transaction(&[missing_authority]))),
+        VerificationError::MissingAccount("user_transfer_authority")
+    );
+}
+
+#[test]
+fn foreign_destination_is_rejected() {
+    let attacker_account = Pubkey::new_unique();
+    let swap_request = swap_request();
+    let verifier = SwapVerifier::new(&swap_request);
+
+    for position in [3, 4] {
+        let mut foreign_destination = route_instruction(1_000_000, 6_000_000, 50);
+        foreign_destination.accounts[position] = AccountMeta::new(attacker_account, false);
+        assert_eq!(
+            verification_error(verifier.verify_transaction(&transaction(&[foreign_destination]))),
+            VerificationError::UnexpectedDestination(attacker_account)
+        );
+    }
+
+    // Unless it is the requested destination
+    let mut swap_request = swap_request;
+    swap_request.config.destination_token_account = Some(attacker_account);
+    let mut requested_destination = route_instruction(1_000_000, 6_000_000, 50);
+    requested_destination.accounts[4] = AccountMeta::new(attacker_account, false);
+    SwapVerifier::new(&swap_request)
+        .verify_transaction(&transaction(&[requested_destination]))
+        .unwrap();
+}
+
+#[test]
+fn other_platform_fee_is_rejected() {
+    let mut swap_request = swap_request();
+    let mut fee_route = route_instruction(1_000_000, 6_000_000, 50);
+    *fee_route.data.last_mut().unwrap() = 20;
+
+    assert_eq!(
+        verification_error(
+            SwapVerifier::new(&swap_request).verify_transaction(&transaction(&[fee_route.clone()]))
+        ),
+        VerificationError::PlatformFeeMismatch {
+            expected: 0,
+            found: 20,
+        }
+    );
+
+    swap_request.quote_response.platform_fee = Some(PlatformFee {
+        amount: 12_000,
+        fee_bps: 20,
+    });
+    SwapVerifier::new(&swap_request)
+        .verify_transaction(&transaction(&[fee_route]))
+        .unwrap();
+}
+
+fn wrapped_sol_account() -> Pubkey {
+    associated_token_address(&TEST_WALLET, &NATIVE_MINT, &TOKEN_PROGRAM_ID)
+}
+
+/// Token program instruction on `accounts`, only the tag of the instruction data is set
+fn token_instruction(tag: u8, accounts: &[Pubkey]) -> Instruction {
+    Instruction::new_with_bytes(
+        TOKEN_PROGRAM_ID,
+        &[tag],
+        accounts
+            .iter()
+            .map(|account| AccountMeta::new(*account, false))
+            .collect(),
+    )
+}
+
+#[test]
+fn only_wrapping_and_unwrapping_token_instructions_are_accepted() {
+    let swap_request = swap_request();
+    let verifier = SwapVerifier::new(&swap_request);
+    let wrapped_sol_account = wrapped_sol_account();
+    let attacker = Pubkey::new_unique();
+    let create_idempotent = Instruction::new_with_bytes(
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        &[1],
+        vec![
+            AccountMeta::new(TEST_WALLET, true),
+            AccountMeta::new(wrapped_sol_account, false),
+            AccountMeta::new_readonly(TEST_WALLET, false),
+            AccountMeta::new_readonly(NATIVE_MINT, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        ],
+    );
+    let close_account =
+        |destination| token_instruction(9, &[wrapped_sol_account, destination, TEST_WALLET]);
+
+    verifier
+        .verify_transaction(&transaction(&[
+            create_idempotent.clone(),
+            system_instruction::transfer(&TEST_WALLET, &wrapped_sol_account, 1_000_000),
+            token_instruction(17, &[wrapped_sol_account]),
+            route_instruction(1_000_000, 6_000_000, 50),
+            close_account(TEST_WALLET),
+        ]))
+        .unwrap();
+
+    assert_eq!(
+        verification_error(verifier.verify_transaction(&transaction(&[
+            route_instruction(1_000_000, 6_000_000, 50),
+            close_account(attacker),
+        ]))),
+        VerificationError::UnexpectedCloseDestination(attacker)
+    );
+    let mut create = create_idempotent;
+    create.data = vec![];
+    assert_eq!(
+        verification_error(verifier.verify_transaction(&transaction(&[
+            create,
+            route_instruction(1_000_000, 6_000_000, 50)
+        ]))),
+        VerificationError::UnexpectedTokenInstruction(ASSOCIATED_TOKEN_PROGRAM_ID)
+    );
+    // Transfer, Approve and SetAuthority
+    for tag in [3, 4, 6] {
+        assert_eq!(
+            verification_error(verifier.verify_transaction(&transaction(&[
+                route_instruction(1_000_000, 6_000_000, 50),
+                token_instruction(tag, &[wrapped_sol_account, attacker, TEST_WALLET]),
+            ]))),
+            VerificationError::UnexpectedTokenInstruction(TOKEN_PROGRAM_ID)
+        );
+    }
+    assert_eq!(
+        verification_error(verifier.verify_transaction(&transaction(&[
+            route_instruction(1_000_000, 6_000_000, 50),
+            token_instruction(17, &[attacker]),
+        ]))),
+        VerificationError::UnexpectedTokenInstruction(TOKEN_PROGRAM_ID)
+    );
+}
+
+#[test]
+fn only_route_and_token_ledger_jupiter_instructions_are_accepted() {
+    let swap_request = swap_request();
+    let verifier = SwapVerifier::new(&swap_request);
+    let set_token_ledger = Instruction::new_with_bytes(
+        JUPITER_PROGRAM_ID,
+        &SET_TOKEN_LEDGER_DISCRIMINATOR,
+        vec![AccountMeta::new(Pubkey::new_unique(), false)],
+    );
+
+    verifier
+        .verify_transaction(&transaction(&[
+            set_token_ledger,
+            route_instruction(1_000_000, 6_000_000, 50),
+        ]))
+        .unwrap();
+    assert_eq!(
+        verification_error(verifier.verify_transaction(&transaction(&[
+            Instruction::new_with_bytes(JUPITER_PROGRAM_ID, &[1, 2, 3], vec![]),
+            route_instruction(1_000_000, 6_000_000, 50),
+        ]))),
+        VerificationError::UnexpectedJupiterInstruction
+    );
+}
+
+#[test]
+fn out_of_range_program_index_is_an_error() {
+    let swap_request = swap_request();
+    let mut transaction = transaction(&[route_instruction(1_000_000, 6_000_000, 50)]);
+    let VersionedMessage::V0(message) = &mut transaction.message else {
+        unreachable!()
+    };
+    message.instructions[0].program_id_index = 9;
+
+    assert_eq!(
+        verification_error(SwapVerifier::new(&swap_request).verify_transaction(&transaction)),
+        VerificationError::UnresolvedAccount(9)
+    );
+}